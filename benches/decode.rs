@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use riscv_instruction_parser::encoder::all_example_encodings;
+use riscv_instruction_parser::parse;
+
+/// A few thousand instructions, cycling through one encoding of every operation.
+fn image() -> Vec<u8> {
+    all_example_encodings()
+        .iter()
+        .cycle()
+        .take(4096)
+        .flat_map(|(_, word)| word.to_le_bytes())
+        .collect()
+}
+
+/// The same number of words, none of which decodes. The reserved opcode `0b1111111` is
+/// combined with varying upper bits.
+fn invalid_image() -> Vec<u8> {
+    (0..4096u32)
+        .flat_map(|i| ((i << 12) | 0b1111111).to_le_bytes())
+        .collect()
+}
+
+fn decode(c: &mut Criterion) {
+    let image = image();
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements((image.len() / 4) as u64));
+    group.bench_function("valid", |b| {
+        b.iter(|| {
+            for word in black_box(&image).chunks_exact(4) {
+                let _ = black_box(parse(word));
+            }
+        })
+    });
+    let invalid_image = invalid_image();
+    group.bench_function("invalid", |b| {
+        b.iter(|| {
+            for word in black_box(&invalid_image).chunks_exact(4) {
+                let _ = black_box(parse(word));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
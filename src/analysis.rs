@@ -0,0 +1,510 @@
+//! Provides helpers for analysing sequences of decoded operations.
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::error::ParseError;
+use crate::instructons::{instruction_length, Instruction, Operation};
+use crate::isa::InstructionSet;
+use crate::parse;
+use crate::registers::Register;
+
+impl Operation {
+    /// Computes the result of a pure integer operation given the values of its source
+    /// registers. Returns None when a needed source value is unknown or when the operation
+    /// accesses memory, changes control flow or has other side effects.
+    ///
+    /// Values are 32 bits wide. The RV64 word operations are folded too since they only depend
+    /// on the low 32 bits of their sources.
+    pub fn folds_to_constant(&self, rs1_known: Option<i32>, rs2_known: Option<i32>) -> Option<i32> {
+        match self {
+            Operation::LUI { imm, .. } => Some(*imm),
+            Operation::ADDI { imm, .. } | Operation::ADDIW { imm, .. } => {
+                Some(rs1_known?.wrapping_add(*imm))
+            }
+            Operation::SLTI { imm, .. } => Some((rs1_known? < *imm) as i32),
+            Operation::SLTIU { imm, .. } => Some(((rs1_known? as u32) < (*imm as u32)) as i32),
+            Operation::XORI { imm, .. } => Some(rs1_known? ^ *imm),
+            Operation::ORI { imm, .. } => Some(rs1_known? | *imm),
+            Operation::ANDI { imm, .. } => Some(rs1_known? & *imm),
+            Operation::SLLI { shamt, .. } | Operation::SLLIW { shamt, .. } if *shamt < 32 => {
+                Some(rs1_known?.wrapping_shl(*shamt as u32))
+            }
+            Operation::SRLI { shamt, .. } | Operation::SRLIW { shamt, .. } if *shamt < 32 => {
+                Some((rs1_known? as u32).wrapping_shr(*shamt as u32) as i32)
+            }
+            Operation::SRAI { shamt, .. } | Operation::SRAIW { shamt, .. } if *shamt < 32 => {
+                Some(rs1_known?.wrapping_shr(*shamt as u32))
+            }
+            Operation::ADD { .. } | Operation::ADDW { .. } => {
+                Some(rs1_known?.wrapping_add(rs2_known?))
+            }
+            Operation::SUB { .. } | Operation::SUBW { .. } => {
+                Some(rs1_known?.wrapping_sub(rs2_known?))
+            }
+            Operation::SLL { .. } | Operation::SLLW { .. } => {
+                Some(rs1_known?.wrapping_shl(rs2_known? as u32))
+            }
+            Operation::SLT { .. } => Some((rs1_known? < rs2_known?) as i32),
+            Operation::SLTU { .. } => Some(((rs1_known? as u32) < (rs2_known? as u32)) as i32),
+            Operation::XOR { .. } => Some(rs1_known? ^ rs2_known?),
+            Operation::SRL { .. } | Operation::SRLW { .. } => {
+                Some((rs1_known? as u32).wrapping_shr(rs2_known? as u32) as i32)
+            }
+            Operation::SRA { .. } | Operation::SRAW { .. } => {
+                Some(rs1_known?.wrapping_shr(rs2_known? as u32))
+            }
+            Operation::OR { .. } => Some(rs1_known? | rs2_known?),
+            Operation::AND { .. } => Some(rs1_known? & rs2_known?),
+            Operation::CLMUL { .. } => Some(clmul(rs1_known?, rs2_known?) as i32),
+            Operation::CLMULH { .. } => Some((clmul(rs1_known?, rs2_known?) >> 32) as i32),
+            Operation::CLMULR { .. } => Some((clmul(rs1_known?, rs2_known?) >> 31) as i32),
+            Operation::SH1ADD { .. } => Some((rs1_known? << 1).wrapping_add(rs2_known?)),
+            Operation::SH2ADD { .. } => Some((rs1_known? << 2).wrapping_add(rs2_known?)),
+            Operation::SH3ADD { .. } => Some((rs1_known? << 3).wrapping_add(rs2_known?)),
+            Operation::ANDN { .. } => Some(rs1_known? & !rs2_known?),
+            Operation::ORN { .. } => Some(rs1_known? | !rs2_known?),
+            Operation::XNOR { .. } => Some(!(rs1_known? ^ rs2_known?)),
+            Operation::MIN { .. } => Some(rs1_known?.min(rs2_known?)),
+            Operation::MINU { .. } => Some((rs1_known? as u32).min(rs2_known? as u32) as i32),
+            Operation::MAX { .. } => Some(rs1_known?.max(rs2_known?)),
+            Operation::MAXU { .. } => Some((rs1_known? as u32).max(rs2_known? as u32) as i32),
+            Operation::ROL { .. } => {
+                Some((rs1_known? as u32).rotate_left(rs2_known? as u32) as i32)
+            }
+            Operation::ROR { .. } => {
+                Some((rs1_known? as u32).rotate_right(rs2_known? as u32) as i32)
+            }
+            Operation::RORI { shamt, .. } if *shamt < 32 => {
+                Some((rs1_known? as u32).rotate_right(*shamt as u32) as i32)
+            }
+            Operation::CLZ { .. } => Some(rs1_known?.leading_zeros() as i32),
+            Operation::CTZ { .. } => Some(rs1_known?.trailing_zeros() as i32),
+            Operation::CPOP { .. } => Some(rs1_known?.count_ones() as i32),
+            Operation::SEXT_B { .. } => Some(rs1_known? as i8 as i32),
+            Operation::SEXT_H { .. } => Some(rs1_known? as i16 as i32),
+            Operation::REV8 { .. } => Some(rs1_known?.swap_bytes()),
+            Operation::ORC_B { .. } => Some(orc_b(rs1_known?)),
+            Operation::ZEXT_H { .. } => Some(rs1_known? & 0xffff),
+            Operation::BCLR { .. } => Some(rs1_known? & !(1 << (rs2_known? & 31))),
+            Operation::BEXT { .. } => Some(((rs1_known? as u32 >> (rs2_known? & 31)) & 1) as i32),
+            Operation::BINV { .. } => Some(rs1_known? ^ (1 << (rs2_known? & 31))),
+            Operation::BSET { .. } => Some(rs1_known? | (1 << (rs2_known? & 31))),
+            Operation::BCLRI { shamt, .. } if *shamt < 32 => Some(rs1_known? & !(1 << shamt)),
+            Operation::BEXTI { shamt, .. } if *shamt < 32 => {
+                Some(((rs1_known? as u32 >> shamt) & 1) as i32)
+            }
+            Operation::BINVI { shamt, .. } if *shamt < 32 => Some(rs1_known? ^ (1 << shamt)),
+            Operation::BSETI { shamt, .. } if *shamt < 32 => Some(rs1_known? | (1 << shamt)),
+            Operation::CZERO_EQZ { .. } => Some(if rs2_known? == 0 { 0 } else { rs1_known? }),
+            Operation::CZERO_NEZ { .. } => Some(if rs2_known? != 0 { 0 } else { rs1_known? }),
+            Operation::MUL { .. } => Some(rs1_known?.wrapping_mul(rs2_known?)),
+            Operation::MULH { .. } => Some(((rs1_known? as i64 * rs2_known? as i64) >> 32) as i32),
+            Operation::MULHSU { .. } => {
+                Some(((rs1_known? as i64 * rs2_known? as u32 as i64) >> 32) as i32)
+            }
+            Operation::MULHU { .. } => {
+                Some(((rs1_known? as u32 as u64 * rs2_known? as u32 as u64) >> 32) as i32)
+            }
+            Operation::DIV { .. } => Some(match rs2_known? {
+                0 => -1,
+                divisor => rs1_known?.wrapping_div(divisor),
+            }),
+            Operation::DIVU { .. } => Some(match rs2_known? as u32 {
+                0 => u32::MAX,
+                divisor => rs1_known? as u32 / divisor,
+            } as i32),
+            Operation::REM { .. } => Some(match rs2_known? {
+                0 => rs1_known?,
+                divisor => rs1_known?.wrapping_rem(divisor),
+            }),
+            Operation::REMU { .. } => Some(match rs2_known? as u32 {
+                0 => rs1_known? as u32,
+                divisor => rs1_known? as u32 % divisor,
+            } as i32),
+            _ => None,
+        }
+    }
+
+    /// Registers read by the operation, in operand order and without duplicates.
+    pub fn uses(&self) -> Vec<Register> {
+        let mut uses: Vec<Register> = self.rs1().into_iter().chain(self.rs2()).collect();
+        uses.dedup();
+        uses
+    }
+
+    /// Registers written by the operation. Writes to `zero` are discarded and not included.
+    pub fn defs(&self) -> Vec<Register> {
+        self.rd()
+            .into_iter()
+            .filter(|rd| *rd != Register::ZERO)
+            .collect()
+    }
+}
+
+/// Full 64 bit carry-less product.
+fn clmul(rs1: i32, rs2: i32) -> u64 {
+    let mut product = 0u64;
+    for i in 0..32 {
+        if (rs2 as u32 >> i) & 0b1 == 0b1 {
+            product ^= (rs1 as u32 as u64) << i;
+        }
+    }
+    product
+}
+
+/// Sets every nonzero byte to `0xff`.
+fn orc_b(rs1: i32) -> i32 {
+    let mut result = 0u32;
+    for i in 0..4 {
+        if (rs1 as u32 >> (i * 8)) & 0xff != 0 {
+            result |= 0xff << (i * 8);
+        }
+    }
+    result as i32
+}
+
+/// Most instructions that an `lr.w`/`sc.w` loop may contain, including the pair itself.
+const LRSC_LOOP_LENGTH: usize = 16;
+
+/// Checks that `lr` and `sc` form a constrained load-reserved/store-conditional loop, i.e. an
+/// `lr.w` followed by an `sc.w` that uses the same address register, which the architecture
+/// guarantees to eventually succeed.
+///
+/// Whether the pair is constrained depends on the code between them, so that is passed as
+/// `between`. It may only hold base integer operations and forward branches, and the whole
+/// sequence at most 16 instructions. Loads, stores, jumps, backward branches, fences, system
+/// instructions and other extensions such as M and F break the loop.
+pub fn check_lrsc_pair(lr: &Operation, between: &[Operation], sc: &Operation) -> bool {
+    let same_base = match (lr, sc) {
+        (Operation::LR_W { rs1: lr_base, .. }, Operation::SC_W { rs1: sc_base, .. }) => {
+            lr_base == sc_base
+        }
+        _ => false,
+    };
+    same_base && between.len() + 2 <= LRSC_LOOP_LENGTH && between.iter().all(allowed_in_lrsc_loop)
+}
+
+/// Base integer computations and forward conditional branches.
+fn allowed_in_lrsc_loop(operation: &Operation) -> bool {
+    let base_integer = operation.is_alu() && operation.extension() == InstructionSet::I;
+    let forward_branch = operation.is_branch()
+        && matches!(operation.branch_offset_bytes(), Some(offset) if offset > 0);
+    base_integer || forward_branch
+}
+
+/// Computes the value that an `lui`, optionally followed by an `addi` on the same register,
+/// leaves in its destination register. Returns None if the operations do not form such a
+/// sequence.
+pub fn loaded_constant(lui: &Operation, addi: Option<&Operation>) -> Option<i32> {
+    let (rd, upper) = match lui {
+        Operation::LUI { rd, imm } => (rd, *imm),
+        _ => return None,
+    };
+    match addi {
+        None => Some(upper),
+        Some(Operation::ADDI {
+            imm,
+            rs1,
+            rd: addi_rd,
+        }) if rs1 == rd && addi_rd == rd => Some(upper.wrapping_add(*imm)),
+        Some(_) => None,
+    }
+}
+
+/// Finds the loop headers in the code `bytes` loaded at address `base`, i.e. the targets of
+/// conditional branches and `jal`s that jump backwards (or to themselves).
+/// Instructions that can not be parsed are skipped.
+pub fn loop_headers(bytes: &[u8], base: u32) -> BTreeSet<u32> {
+    let mut headers = BTreeSet::new();
+    for_each_instruction(bytes, base, |pc, instruction| {
+        if let Ok(instruction) = instruction {
+            if let Some(target) = static_target(&instruction.operation, pc) {
+                if target <= pc {
+                    headers.insert(target);
+                }
+            }
+        }
+    });
+    headers
+}
+
+/// Collects the targets of all conditional branches and `jal`s in the code `bytes` loaded at
+/// address `base`. Instructions that can not be parsed are skipped.
+pub fn collect_targets(bytes: &[u8], base: u32) -> BTreeSet<u32> {
+    let mut targets = BTreeSet::new();
+    for_each_instruction(bytes, base, |pc, instruction| {
+        if let Ok(instruction) = instruction {
+            if let Some(target) = static_target(&instruction.operation, pc) {
+                targets.insert(target);
+            }
+        }
+    });
+    targets
+}
+
+/// The pc-relative target of a conditional branch or `jal`.
+pub(crate) fn static_target(operation: &Operation, pc: u32) -> Option<u32> {
+    match operation {
+        Operation::JAL { .. } => operation.jump_target(pc),
+        _ => operation.branch_target(pc),
+    }
+}
+
+/// Parses the code `bytes` loaded at address `base` one instruction at a time, calling `f`
+/// with the address and result of each. Stops at a trailing incomplete instruction.
+pub(crate) fn for_each_instruction<F>(bytes: &[u8], base: u32, mut f: F)
+where
+    F: FnMut(u32, Result<Instruction, ParseError>),
+{
+    let mut offset = 0;
+    while offset + 2 <= bytes.len() {
+        let size = instruction_length(<u16>::from_le_bytes([bytes[offset], bytes[offset + 1]]));
+        if offset + size > bytes.len() {
+            break;
+        }
+        f(base.wrapping_add(offset as u32), parse(&bytes[offset..]));
+        offset += size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn lr(base: Register) -> Operation {
+        Operation::LR_W {
+            rs1: base,
+            rd: Register::T0,
+            aq: true,
+            rl: false,
+        }
+    }
+
+    fn sc(base: Register) -> Operation {
+        Operation::SC_W {
+            rs2: Register::T1,
+            rs1: base,
+            rd: Register::T2,
+            aq: false,
+            rl: true,
+        }
+    }
+
+    #[test]
+    fn lrsc_pair() {
+        assert!(check_lrsc_pair(&lr(Register::A0), &[], &sc(Register::A0)));
+        assert!(!check_lrsc_pair(&lr(Register::A0), &[], &sc(Register::A1)));
+        assert!(!check_lrsc_pair(&sc(Register::A0), &[], &lr(Register::A0)));
+    }
+
+    #[test]
+    fn lrsc_pair_with_operations_between() {
+        let addi = Operation::ADDI {
+            imm: 1,
+            rs1: Register::T0,
+            rd: Register::T1,
+        };
+        // bne t0, t1, 8 skipping forward
+        let forward = Operation::BNE {
+            imm: 8,
+            rs1: Register::T0,
+            rs2: Register::T1,
+        };
+        assert!(check_lrsc_pair(
+            &lr(Register::A0),
+            &[addi, forward],
+            &sc(Register::A0)
+        ));
+        let sw = Operation::SW {
+            imm: 0,
+            rs1: Register::A1,
+            rs2: Register::T1,
+        };
+        let backward = Operation::BNE {
+            imm: -8,
+            rs1: Register::T0,
+            rs2: Register::T1,
+        };
+        let jalr = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::RA,
+            imm: 0,
+        };
+        let fence = Operation::FENCE {
+            fm: 0,
+            pred: 0b1111,
+            succ: 0b1111,
+        };
+        let mul = Operation::MUL {
+            rs2: Register::T0,
+            rs1: Register::T0,
+            rd: Register::T1,
+        };
+        // a stray sc.w after another lr/sc sequence does not pair with the first lr.w
+        for breaking in [
+            sw,
+            backward,
+            jalr,
+            fence,
+            Operation::ECALL {},
+            mul,
+            sc(Register::A0),
+            lr(Register::A0),
+        ] {
+            assert!(
+                !check_lrsc_pair(&lr(Register::A0), &[addi, breaking], &sc(Register::A0)),
+                "{}",
+                breaking
+            );
+        }
+    }
+
+    #[test]
+    fn lrsc_loop_length() {
+        let addi = Operation::ADDI {
+            imm: 1,
+            rs1: Register::T0,
+            rd: Register::T1,
+        };
+        assert!(check_lrsc_pair(
+            &lr(Register::A0),
+            &[addi; 14],
+            &sc(Register::A0)
+        ));
+        assert!(!check_lrsc_pair(
+            &lr(Register::A0),
+            &[addi; 15],
+            &sc(Register::A0)
+        ));
+    }
+
+    #[test]
+    fn lui_constant() {
+        let lui = Operation::LUI {
+            rd: Register::A0,
+            imm: 0x12346000,
+        };
+        assert_eq!(loaded_constant(&lui, None), Some(0x12346000));
+        // the low part is sign extended, so the upper part is rounded up
+        let addi = Operation::ADDI {
+            imm: -1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(loaded_constant(&lui, Some(&addi)), Some(0x12345fff));
+        let other = Operation::ADDI {
+            imm: -1,
+            rs1: Register::A1,
+            rd: Register::A0,
+        };
+        assert_eq!(loaded_constant(&lui, Some(&other)), None);
+        assert_eq!(loaded_constant(&addi, None), None);
+    }
+
+    #[test]
+    fn backward_branch_is_loop_header() {
+        let program = [
+            0x13, 0x05, 0xa0, 0x00, // 0x100: addi a0, zero, 10
+            0x13, 0x05, 0xf5, 0xff, // 0x104: addi a0, a0, -1
+            0xe3, 0x1e, 0x05, 0xfe, // 0x108: bnez a0, 0x104
+            0x63, 0x04, 0x00, 0x00, // 0x10c: beqz zero, 0x114
+            0x67, 0x80, 0x00, 0x00, // 0x110: ret
+        ];
+        assert_eq!(loop_headers(&program, 0x100), BTreeSet::from([0x104]));
+    }
+    #[test]
+    fn constant_folding() {
+        let addi = Operation::ADDI {
+            imm: -12,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(addi.folds_to_constant(Some(10), None), Some(-2));
+        assert_eq!(addi.folds_to_constant(None, None), None);
+        assert_eq!(
+            addi.folds_to_constant(Some(i32::MIN), None),
+            Some(i32::MIN.wrapping_sub(12))
+        );
+        let slt = Operation::SLT {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(slt.folds_to_constant(Some(-1), Some(1)), Some(1));
+        assert_eq!(slt.folds_to_constant(Some(1), Some(-1)), Some(0));
+        assert_eq!(slt.folds_to_constant(Some(1), None), None);
+        let sltu = Operation::SLTU {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(sltu.folds_to_constant(Some(-1), Some(1)), Some(0));
+        let sra = Operation::SRA {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        // only the low five bits of the shift amount are used
+        assert_eq!(sra.folds_to_constant(Some(-16), Some(34)), Some(-4));
+        let clmulh = Operation::CLMULH {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(
+            clmulh.folds_to_constant(Some(-1), Some(-1)),
+            Some(0x55555555)
+        );
+        let lw = Operation::LW {
+            imm: 0,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(lw.folds_to_constant(Some(0x1000), None), None);
+    }
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn targets() {
+        let program = [
+            0x63, 0x04, 0x00, 0x00, // 0x100: beqz zero, 0x108
+            0x21, 0x20, //             0x104: c.jal 0x10c
+            0x01, 0x00, //             0x106: c.nop
+            0xe3, 0x1c, 0x05, 0xfe, // 0x108: bnez a0, 0x100
+            0x67, 0x80, 0x00, 0x00, // 0x10c: ret
+            0x63, 0x04, //             truncated
+        ];
+        assert_eq!(
+            collect_targets(&program, 0x100),
+            BTreeSet::from([0x100, 0x108, 0x10c])
+        );
+        assert_eq!(loop_headers(&program, 0x100), BTreeSet::from([0x100]));
+    }
+
+    #[test]
+    fn def_use() {
+        // sw a0, 8(sp)
+        let sw = parse(&[0x23, 0x24, 0xa1, 0x00]).unwrap().operation;
+        assert_eq!(sw.uses(), vec![Register::SP, Register::A0]);
+        assert_eq!(sw.defs(), vec![]);
+        // lw a0, 8(sp)
+        let lw = parse(&[0x03, 0x25, 0x81, 0x00]).unwrap().operation;
+        assert_eq!(lw.uses(), vec![Register::SP]);
+        assert_eq!(lw.defs(), vec![Register::A0]);
+        // bne a0, zero, -4
+        let bne = parse(&[0xe3, 0x1e, 0x05, 0xfe]).unwrap().operation;
+        assert_eq!(bne.uses(), vec![Register::A0, Register::ZERO]);
+        assert_eq!(bne.defs(), vec![]);
+        // add a1, a0, a0
+        let add = parse(&[0xb3, 0x05, 0xa5, 0x00]).unwrap().operation;
+        assert_eq!(add.uses(), vec![Register::A0]);
+        assert_eq!(add.defs(), vec![Register::A1]);
+        // jal ra, 8 ; jal zero, 8
+        let jal = parse(&[0xef, 0x00, 0x80, 0x00]).unwrap().operation;
+        assert_eq!(jal.uses(), vec![]);
+        assert_eq!(jal.defs(), vec![Register::RA]);
+        let j = parse(&[0x6f, 0x00, 0x80, 0x00]).unwrap().operation;
+        assert_eq!(j.defs(), vec![]);
+    }
+}
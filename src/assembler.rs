@@ -0,0 +1,690 @@
+//! Provides parsing of single lines of assembly back into operations.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::csr::csr_number;
+use crate::instructons::Operation;
+use crate::registers::{FloatRegister, Register};
+use crate::rounding::RoundingMode;
+
+/// Parses one line of assembly in the syntax operations are formatted in, e.g.
+/// `addi a0, a0, -1`, `lw a0, 8(sp)` or `amoadd.w.aq a0, a2, (a1)`, as well as the output of
+/// [`gas_line`](crate::disassembler::gas_line): offsets relative to the location counter,
+/// e.g. `beq a0, a1, .+0x10`, and `.word`/`.half` directives, which give an
+/// [`Operation::Unknown`]. Pseudo-instructions are not accepted.
+/// Returns Err(String) if the mnemonic or an operand can not be parsed.
+pub fn parse_line(line: &str) -> Result<Operation, String> {
+    let line = line.trim();
+    let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let (mnemonic, aq, rl) = split_ordering(mnemonic);
+    let mut ops = Operands::new(operands);
+    // struct fields are evaluated in the order they are written, which is the operand order
+    let operation = match mnemonic {
+        ".word" => Operation::Unknown { raw: ops.raw(32)? },
+        ".half" => Operation::Unknown { raw: ops.raw(16)? },
+        "lui" => Operation::LUI {
+            rd: ops.register()?,
+            imm: ops.upper()?,
+        },
+        "auipc" => Operation::AUIPC {
+            rd: ops.register()?,
+            imm: ops.upper()?,
+        },
+        "jal" => Operation::JAL {
+            rd: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "jalr" => {
+            let rd = ops.register()?;
+            let (imm, rs1) = ops.memory()?;
+            Operation::JALR { rd, rs1, imm }
+        }
+        "beq" => Operation::BEQ {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "bne" => Operation::BNE {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "blt" => Operation::BLT {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "bge" => Operation::BGE {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "bltu" => Operation::BLTU {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "bgeu" => Operation::BGEU {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+            imm: ops.offset()?,
+        },
+        "lb" | "lh" | "lw" | "lbu" | "lhu" | "lwu" | "ld" => {
+            let rd = ops.register()?;
+            let (imm, rs1) = ops.memory()?;
+            match mnemonic {
+                "lb" => Operation::LB { imm, rs1, rd },
+                "lh" => Operation::LH { imm, rs1, rd },
+                "lw" => Operation::LW { imm, rs1, rd },
+                "lbu" => Operation::LBU { imm, rs1, rd },
+                "lhu" => Operation::LHU { imm, rs1, rd },
+                "lwu" => Operation::LWU { imm, rs1, rd },
+                _ => Operation::LD { imm, rs1, rd },
+            }
+        }
+        "sb" | "sh" | "sw" | "sd" => {
+            let rs2 = ops.register()?;
+            let (imm, rs1) = ops.memory()?;
+            match mnemonic {
+                "sb" => Operation::SB { imm, rs2, rs1 },
+                "sh" => Operation::SH { imm, rs2, rs1 },
+                "sw" => Operation::SW { imm, rs2, rs1 },
+                _ => Operation::SD { imm, rs2, rs1 },
+            }
+        }
+        "addi" | "slti" | "sltiu" | "xori" | "ori" | "andi" | "addiw" => {
+            let (rd, rs1, imm) = (ops.register()?, ops.register()?, ops.immediate()?);
+            match mnemonic {
+                "addi" => Operation::ADDI { imm, rs1, rd },
+                "slti" => Operation::SLTI { imm, rs1, rd },
+                "sltiu" => Operation::SLTIU { imm, rs1, rd },
+                "xori" => Operation::XORI { imm, rs1, rd },
+                "ori" => Operation::ORI { imm, rs1, rd },
+                "andi" => Operation::ANDI { imm, rs1, rd },
+                _ => Operation::ADDIW { imm, rs1, rd },
+            }
+        }
+        "slli" | "srli" | "srai" | "slliw" | "srliw" | "sraiw" | "rori" | "bclri" | "bexti"
+        | "binvi" | "bseti" => {
+            let (rd, rs1, shamt) = (ops.register()?, ops.register()?, ops.small(6)?);
+            match mnemonic {
+                "slli" => Operation::SLLI { shamt, rs1, rd },
+                "srli" => Operation::SRLI { shamt, rs1, rd },
+                "srai" => Operation::SRAI { shamt, rs1, rd },
+                "slliw" => Operation::SLLIW { shamt, rs1, rd },
+                "srliw" => Operation::SRLIW { shamt, rs1, rd },
+                "sraiw" => Operation::SRAIW { shamt, rs1, rd },
+                "rori" => Operation::RORI { shamt, rs1, rd },
+                "bclri" => Operation::BCLRI { shamt, rs1, rd },
+                "bexti" => Operation::BEXTI { shamt, rs1, rd },
+                "binvi" => Operation::BINVI { shamt, rs1, rd },
+                _ => Operation::BSETI { shamt, rs1, rd },
+            }
+        }
+        "clz" | "ctz" | "cpop" | "sext.b" | "sext.h" | "rev8" | "orc.b" | "zext.h" => {
+            let (rd, rs1) = (ops.register()?, ops.register()?);
+            match mnemonic {
+                "clz" => Operation::CLZ { rs1, rd },
+                "ctz" => Operation::CTZ { rs1, rd },
+                "cpop" => Operation::CPOP { rs1, rd },
+                "sext.b" => Operation::SEXT_B { rs1, rd },
+                "sext.h" => Operation::SEXT_H { rs1, rd },
+                "rev8" => Operation::REV8 { rs1, rd },
+                "orc.b" => Operation::ORC_B { rs1, rd },
+                _ => Operation::ZEXT_H { rs1, rd },
+            }
+        }
+        "fence" => Operation::FENCE {
+            fm: 0,
+            pred: ops.fence_set()?,
+            succ: ops.fence_set()?,
+        },
+        "fence.tso" => Operation::FENCE {
+            fm: 0b1000,
+            pred: 0b0011,
+            succ: 0b0011,
+        },
+        "fence.i" => Operation::FENCE_I {},
+        "pause" => Operation::PAUSE {},
+        "ecall" => Operation::ECALL {},
+        "ebreak" => Operation::EBREAK {},
+        "mret" => Operation::MRET {},
+        "sfence.vma" => Operation::SFENCE_VMA {
+            rs1: ops.register()?,
+            rs2: ops.register()?,
+        },
+        "csrrw" => Operation::CSRRW {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            rs1: ops.register()?,
+        },
+        "csrrs" => Operation::CSRRS {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            rs1: ops.register()?,
+        },
+        "csrrc" => Operation::CSRRC {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            rs1: ops.register()?,
+        },
+        "csrrwi" => Operation::CSRRWI {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            zimm: ops.small(5)?,
+        },
+        "csrrsi" => Operation::CSRRSI {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            zimm: ops.small(5)?,
+        },
+        "csrrci" => Operation::CSRRCI {
+            rd: ops.register()?,
+            csr: ops.csr()?,
+            zimm: ops.small(5)?,
+        },
+        "lr.w" => Operation::LR_W {
+            rd: ops.register()?,
+            rs1: ops.address()?,
+            aq,
+            rl,
+        },
+        "sc.w" | "amoswap.w" | "amoadd.w" | "amoxor.w" | "amoand.w" | "amoor.w" | "amomin.w"
+        | "amomax.w" | "amominu.w" | "amomaxu.w" => {
+            let (rd, rs2, rs1) = (ops.register()?, ops.register()?, ops.address()?);
+            match mnemonic {
+                "sc.w" => Operation::SC_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amoswap.w" => Operation::AMOSWAP_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amoadd.w" => Operation::AMOADD_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amoxor.w" => Operation::AMOXOR_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amoand.w" => Operation::AMOAND_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amoor.w" => Operation::AMOOR_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amomin.w" => Operation::AMOMIN_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amomax.w" => Operation::AMOMAX_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                "amominu.w" => Operation::AMOMINU_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+                _ => Operation::AMOMAXU_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                },
+            }
+        }
+        "flw" => {
+            let rd = ops.float_register()?;
+            let (imm, rs1) = ops.memory()?;
+            Operation::FLW { imm, rs1, rd }
+        }
+        "fsw" => {
+            let rs2 = ops.float_register()?;
+            let (imm, rs1) = ops.memory()?;
+            Operation::FSW { imm, rs2, rs1 }
+        }
+        "fadd.s" | "fsub.s" | "fmul.s" | "fdiv.s" => {
+            let (rd, rs1, rs2) = (
+                ops.float_register()?,
+                ops.float_register()?,
+                ops.float_register()?,
+            );
+            let rm = ops.rounding_mode()?;
+            match mnemonic {
+                "fadd.s" => Operation::FADD_S { rs2, rs1, rd, rm },
+                "fsub.s" => Operation::FSUB_S { rs2, rs1, rd, rm },
+                "fmul.s" => Operation::FMUL_S { rs2, rs1, rd, rm },
+                _ => Operation::FDIV_S { rs2, rs1, rd, rm },
+            }
+        }
+        "fsqrt.s" => Operation::FSQRT_S {
+            rd: ops.float_register()?,
+            rs1: ops.float_register()?,
+            rm: ops.rounding_mode()?,
+        },
+        "fmadd.s" | "fmsub.s" | "fnmsub.s" | "fnmadd.s" => {
+            let (rd, rs1, rs2, rs3) = (
+                ops.float_register()?,
+                ops.float_register()?,
+                ops.float_register()?,
+                ops.float_register()?,
+            );
+            let rm = ops.rounding_mode()?;
+            match mnemonic {
+                "fmadd.s" => Operation::FMADD_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                },
+                "fmsub.s" => Operation::FMSUB_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                },
+                "fnmsub.s" => Operation::FNMSUB_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                },
+                _ => Operation::FNMADD_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                },
+            }
+        }
+        _ => {
+            let (rd, rs1, rs2) = (ops.register()?, ops.register()?, ops.register()?);
+            register_operation(mnemonic, rd, rs1, rs2)
+                .ok_or_else(|| format!("Unknown mnemonic {}", mnemonic))?
+        }
+    };
+    ops.finish()?;
+    Ok(operation)
+}
+
+/// Builds the operations that take three integer registers, `mn rd, rs1, rs2`.
+fn register_operation(
+    mnemonic: &str,
+    rd: Register,
+    rs1: Register,
+    rs2: Register,
+) -> Option<Operation> {
+    let operation = match mnemonic {
+        "add" => Operation::ADD { rs2, rs1, rd },
+        "sub" => Operation::SUB { rs2, rs1, rd },
+        "sll" => Operation::SLL { rs2, rs1, rd },
+        "slt" => Operation::SLT { rs2, rs1, rd },
+        "sltu" => Operation::SLTU { rs2, rs1, rd },
+        "xor" => Operation::XOR { rs2, rs1, rd },
+        "srl" => Operation::SRL { rs2, rs1, rd },
+        "sra" => Operation::SRA { rs2, rs1, rd },
+        "or" => Operation::OR { rs2, rs1, rd },
+        "and" => Operation::AND { rs2, rs1, rd },
+        "mul" => Operation::MUL { rs2, rs1, rd },
+        "mulh" => Operation::MULH { rs2, rs1, rd },
+        "mulhsu" => Operation::MULHSU { rs2, rs1, rd },
+        "mulhu" => Operation::MULHU { rs2, rs1, rd },
+        "div" => Operation::DIV { rs2, rs1, rd },
+        "divu" => Operation::DIVU { rs2, rs1, rd },
+        "rem" => Operation::REM { rs2, rs1, rd },
+        "remu" => Operation::REMU { rs2, rs1, rd },
+        "addw" => Operation::ADDW { rs2, rs1, rd },
+        "subw" => Operation::SUBW { rs2, rs1, rd },
+        "sllw" => Operation::SLLW { rs2, rs1, rd },
+        "srlw" => Operation::SRLW { rs2, rs1, rd },
+        "sraw" => Operation::SRAW { rs2, rs1, rd },
+        "clmul" => Operation::CLMUL { rs2, rs1, rd },
+        "clmulh" => Operation::CLMULH { rs2, rs1, rd },
+        "clmulr" => Operation::CLMULR { rs2, rs1, rd },
+        "sh1add" => Operation::SH1ADD { rs2, rs1, rd },
+        "sh2add" => Operation::SH2ADD { rs2, rs1, rd },
+        "sh3add" => Operation::SH3ADD { rs2, rs1, rd },
+        "andn" => Operation::ANDN { rs2, rs1, rd },
+        "orn" => Operation::ORN { rs2, rs1, rd },
+        "xnor" => Operation::XNOR { rs2, rs1, rd },
+        "min" => Operation::MIN { rs2, rs1, rd },
+        "minu" => Operation::MINU { rs2, rs1, rd },
+        "max" => Operation::MAX { rs2, rs1, rd },
+        "maxu" => Operation::MAXU { rs2, rs1, rd },
+        "rol" => Operation::ROL { rs2, rs1, rd },
+        "ror" => Operation::ROR { rs2, rs1, rd },
+        "bclr" => Operation::BCLR { rs2, rs1, rd },
+        "bext" => Operation::BEXT { rs2, rs1, rd },
+        "binv" => Operation::BINV { rs2, rs1, rd },
+        "bset" => Operation::BSET { rs2, rs1, rd },
+        "czero.eqz" => Operation::CZERO_EQZ { rs2, rs1, rd },
+        "czero.nez" => Operation::CZERO_NEZ { rs2, rs1, rd },
+        _ => return None,
+    };
+    Some(operation)
+}
+
+/// Splits the `.aq`, `.rl` or `.aqrl` suffix off the mnemonic of an atomic.
+fn split_ordering(mnemonic: &str) -> (&str, bool, bool) {
+    if !["lr.", "sc.", "amo"]
+        .iter()
+        .any(|p| mnemonic.starts_with(p))
+    {
+        return (mnemonic, false, false);
+    }
+    for (suffix, aq, rl) in [
+        (".aqrl", true, true),
+        (".aq", true, false),
+        (".rl", false, true),
+    ] {
+        if let Some(base) = mnemonic.strip_suffix(suffix) {
+            return (base, aq, rl);
+        }
+    }
+    (mnemonic, false, false)
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal number with an optional `-` sign.
+fn number(text: &str) -> Option<i64> {
+    let (negative, magnitude) = match text.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, text),
+    };
+    let value = match magnitude.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => magnitude.parse::<u32>().ok()?,
+    } as i64;
+    Some(if negative { -value } else { value })
+}
+
+/// The comma separated operands of a line, consumed from left to right.
+struct Operands<'a> {
+    list: Vec<&'a str>,
+    next: usize,
+}
+
+impl<'a> Operands<'a> {
+    fn new(text: &'a str) -> Self {
+        let list = match text.trim() {
+            "" => Vec::new(),
+            text => text.split(',').map(str::trim).collect(),
+        };
+        Operands { list, next: 0 }
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let operand = self.list.get(self.next).ok_or("Missing operand")?;
+        self.next += 1;
+        Ok(operand)
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.list.get(self.next) {
+            Some(operand) => Err(format!("Unexpected operand {}", operand)),
+            None => Ok(()),
+        }
+    }
+
+    fn register(&mut self) -> Result<Register, String> {
+        let operand = self.next()?;
+        operand
+            .parse()
+            .map_err(|_| format!("Invalid register {}", operand))
+    }
+
+    fn float_register(&mut self) -> Result<FloatRegister, String> {
+        let operand = self.next()?;
+        FloatRegister::from_abi_name(operand)
+            .ok_or_else(|| format!("Invalid floating-point register {}", operand))
+    }
+
+    fn immediate(&mut self) -> Result<i32, String> {
+        let operand = self.next()?;
+        number(operand)
+            .and_then(|value| i32::try_from(value).ok())
+            .ok_or_else(|| format!("Invalid immediate {}", operand))
+    }
+
+    /// An unsigned immediate of at most `bits` bits, i.e. a shift amount or a CSR `zimm`.
+    fn small(&mut self, bits: u32) -> Result<u8, String> {
+        let operand = self.next()?;
+        match number(operand) {
+            Some(value) if (0..1 << bits).contains(&value) => Ok(value as u8),
+            _ => Err(format!("Invalid immediate {}", operand)),
+        }
+    }
+
+    /// The upper 20 bits of `lui` and `auipc`, returned shifted into place.
+    fn upper(&mut self) -> Result<i32, String> {
+        let operand = self.next()?;
+        match number(operand) {
+            Some(value) if (0..1 << 20).contains(&value) => Ok(((value as u32) << 12) as i32),
+            _ => Err(format!("Invalid immediate {}", operand)),
+        }
+    }
+
+    /// A branch or jump offset, either plain or relative to the location counter, e.g.
+    /// `-0x8` or `.-0x8`.
+    fn offset(&mut self) -> Result<i32, String> {
+        let operand = self.next()?;
+        let relative = match operand.strip_prefix('.') {
+            Some("") => Some(0),
+            Some(offset) => number(offset.strip_prefix('+').unwrap_or(offset)),
+            None => number(operand),
+        };
+        relative
+            .and_then(|value| i32::try_from(value).ok())
+            .ok_or_else(|| format!("Invalid offset {}", operand))
+    }
+
+    /// A memory operand `offset(base)`, the offset defaulting to 0.
+    fn memory(&mut self) -> Result<(i32, Register), String> {
+        let operand = self.next()?;
+        let invalid = || format!("Invalid memory operand {}", operand);
+        let (offset, base) = operand.split_once('(').ok_or_else(invalid)?;
+        let base = base.strip_suffix(')').ok_or_else(invalid)?;
+        let offset = match offset.trim() {
+            "" => 0,
+            offset => number(offset)
+                .and_then(|value| i32::try_from(value).ok())
+                .ok_or_else(invalid)?,
+        };
+        let base = base.trim().parse().map_err(|_| invalid())?;
+        Ok((offset, base))
+    }
+
+    /// The address register of an atomic, `(rs1)`.
+    fn address(&mut self) -> Result<Register, String> {
+        let operand = self.list.get(self.next).copied();
+        match self.memory()? {
+            (0, base) => Ok(base),
+            _ => Err(format!("Invalid address {}", operand.unwrap_or_default())),
+        }
+    }
+
+    fn csr(&mut self) -> Result<u16, String> {
+        let operand = self.next()?;
+        match (csr_number(operand), number(operand)) {
+            (Some(csr), _) => Ok(csr),
+            (None, Some(csr)) if (0..0x1000).contains(&csr) => Ok(csr as u16),
+            _ => Err(format!("Invalid CSR {}", operand)),
+        }
+    }
+
+    /// The predecessor or successor set of a `fence`, e.g. `rw`, or `0` for the empty set.
+    fn fence_set(&mut self) -> Result<u8, String> {
+        let operand = self.next()?;
+        if operand == "0" {
+            return Ok(0);
+        }
+        operand.chars().try_fold(0, |set, access| {
+            let bit = match access {
+                'i' => 0b1000,
+                'o' => 0b0100,
+                'r' => 0b0010,
+                'w' => 0b0001,
+                _ => return Err(format!("Invalid fence set {}", operand)),
+            };
+            Ok(set | bit)
+        })
+    }
+
+    /// The optional trailing rounding mode of a floating-point operation, `dyn` if omitted.
+    fn rounding_mode(&mut self) -> Result<RoundingMode, String> {
+        if self.next == self.list.len() {
+            return Ok(RoundingMode::Dyn);
+        }
+        let operand = self.next()?;
+        [
+            RoundingMode::Rne,
+            RoundingMode::Rtz,
+            RoundingMode::Rdn,
+            RoundingMode::Rup,
+            RoundingMode::Rmm,
+            RoundingMode::Dyn,
+        ]
+        .into_iter()
+        .find(|rm| rm.name() == operand)
+        .ok_or_else(|| format!("Invalid rounding mode {}", operand))
+    }
+
+    /// A `.word` or `.half` value of at most `bits` bits.
+    fn raw(&mut self, bits: u32) -> Result<u32, String> {
+        let operand = self.next()?;
+        match number(operand) {
+            Some(value) if (0..1 << bits).contains(&value) => Ok(value as u32),
+            _ => Err(format!("Invalid value {}", operand)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operand_shapes() {
+        assert_eq!(
+            parse_line("addi a0, a0, -1"),
+            Ok(Operation::ADDI {
+                imm: -1,
+                rs1: Register::A0,
+                rd: Register::A0
+            })
+        );
+        assert_eq!(
+            parse_line("sw ra, 12(sp)"),
+            Ok(Operation::SW {
+                imm: 12,
+                rs2: Register::RA,
+                rs1: Register::SP
+            })
+        );
+        assert_eq!(
+            parse_line("bne a0, x0, .-0x8"),
+            Ok(Operation::BNE {
+                imm: -8,
+                rs1: Register::A0,
+                rs2: Register::ZERO
+            })
+        );
+        assert_eq!(
+            parse_line("lui a0, 0xfffff"),
+            Ok(Operation::LUI {
+                rd: Register::A0,
+                imm: -0x1000
+            })
+        );
+        assert_eq!(
+            parse_line("csrrs a0, mstatus, zero"),
+            Ok(Operation::CSRRS {
+                csr: 0x300,
+                rs1: Register::ZERO,
+                rd: Register::A0
+            })
+        );
+        assert_eq!(
+            parse_line("amoadd.w.aqrl a0, a2, (a1)"),
+            Ok(Operation::AMOADD_W {
+                rs2: Register::A2,
+                rs1: Register::A1,
+                rd: Register::A0,
+                aq: true,
+                rl: true
+            })
+        );
+        assert_eq!(
+            parse_line("fadd.s fa0, fa1, fa2, rtz"),
+            Ok(Operation::FADD_S {
+                rs2: FloatRegister::F12,
+                rs1: FloatRegister::F11,
+                rd: FloatRegister::F10,
+                rm: RoundingMode::Rtz
+            })
+        );
+        assert_eq!(
+            parse_line("fence iorw, 0"),
+            Ok(Operation::FENCE {
+                fm: 0,
+                pred: 0b1111,
+                succ: 0
+            })
+        );
+        assert_eq!(
+            parse_line(".half 0x0000"),
+            Ok(Operation::Unknown { raw: 0 })
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(parse_line("nop").is_err());
+        assert!(parse_line("add a0, a1").is_err());
+        assert!(parse_line("add a0, a1, a2, a3").is_err());
+        assert!(parse_line("addi a0, a1, ten").is_err());
+        assert!(parse_line("lw a0, 8(q0)").is_err());
+        assert!(parse_line("lui a0, 0x100000").is_err());
+        assert!(parse_line("slli a0, a0, 64").is_err());
+        assert!(parse_line("lr.w a0, 4(a1)").is_err());
+        assert!(parse_line("add.aq a0, a1, a2").is_err());
+        assert!(parse_line(".half 0x10000").is_err());
+    }
+}
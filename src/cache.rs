@@ -0,0 +1,171 @@
+//! Provides a cache of decoded instructions keyed by program counter.
+
+use alloc::collections::btree_map::Entry;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::instructons::Instruction;
+
+/// Cache mapping program counter values to already decoded instructions.
+///
+/// Meant for interpreter loops that revisit the same addresses, so that neither the
+/// memory read nor the decode has to be repeated. Entries overlapping memory that is
+/// written to must be dropped with [`PcDecodeCache::invalidate`].
+#[derive(Debug, Default)]
+pub struct PcDecodeCache {
+    entries: BTreeMap<u32, Instruction>,
+}
+
+impl PcDecodeCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached instruction at `pc`, if any.
+    pub fn get(&self, pc: u32) -> Option<&Instruction> {
+        self.entries.get(&pc)
+    }
+
+    /// Returns the cached instruction at `pc`, calling `decode` to produce it on a miss.
+    /// Errors from `decode` are passed through and nothing is cached.
+    pub fn get_or_decode<F, E>(&mut self, pc: u32, decode: F) -> Result<&Instruction, E>
+    where
+        F: FnOnce(u32) -> Result<Instruction, E>,
+    {
+        match self.entries.entry(pc) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(decode(pc)?)),
+        }
+    }
+
+    /// Inserts a decoded instruction at `pc`, replacing any previous entry.
+    pub fn insert(&mut self, pc: u32, instruction: Instruction) {
+        self.entries.insert(pc, instruction);
+    }
+
+    /// Drops every cached instruction whose bytes overlap `pc_range`.
+    pub fn invalidate(&mut self, pc_range: Range<u32>) {
+        if pc_range.is_empty() {
+            return;
+        }
+        // An instruction starting a few bytes before the range can still reach into it.
+        let first = pc_range.start.saturating_sub(3);
+        let stale: Vec<u32> = self
+            .entries
+            .range(first..pc_range.end)
+            .filter(|(pc, instruction)| {
+                pc.saturating_add(instruction.width.bytes()) > pc_range.start
+            })
+            .map(|(pc, _)| *pc)
+            .collect();
+        for pc in stale {
+            self.entries.remove(&pc);
+        }
+    }
+
+    /// Removes all cached instructions.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached instructions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// To check if the cache holds no instructions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructons::Operation;
+    use crate::parse;
+    use crate::registers::Register;
+    use alloc::format;
+    use core::cell::Cell;
+
+    // addi a0, a0, 1 ; add a1, a0, a0
+    const PROGRAM: [u8; 8] = [0x13, 0x05, 0x15, 0x00, 0xb3, 0x05, 0xa5, 0x00];
+
+    #[test]
+    fn cached_pc_returns_same_instruction() {
+        let mut cache = PcDecodeCache::new();
+        let decodes = Cell::new(0);
+        let decode = |pc: u32| {
+            decodes.set(decodes.get() + 1);
+            parse(&PROGRAM[pc as usize..])
+        };
+
+        let first = format!("{:?}", cache.get_or_decode(4, decode).unwrap());
+        let second = format!("{:?}", cache.get_or_decode(4, decode).unwrap());
+        assert_eq!(first, second);
+        assert_eq!(decodes.get(), 1);
+        assert_eq!(
+            cache.get(4).unwrap().operation,
+            Operation::ADD {
+                rs2: Register::A0,
+                rs1: Register::A0,
+                rd: Register::A1,
+            }
+        );
+    }
+
+    #[test]
+    fn invalidation_forces_redecode() {
+        let mut cache = PcDecodeCache::new();
+        let decodes = Cell::new(0);
+        let decode = |pc: u32| {
+            decodes.set(decodes.get() + 1);
+            parse(&PROGRAM[pc as usize..])
+        };
+
+        cache.get_or_decode(0, decode).unwrap();
+        cache.get_or_decode(4, decode).unwrap();
+        assert_eq!(decodes.get(), 2);
+
+        // A write to the last byte of the first instruction only affects that one.
+        cache.invalidate(3..4);
+        assert!(cache.get(0).is_none());
+        assert!(cache.get(4).is_some());
+
+        cache.get_or_decode(0, decode).unwrap();
+        cache.get_or_decode(4, decode).unwrap();
+        assert_eq!(decodes.get(), 3);
+
+        cache.invalidate(0..8);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn decode_errors_are_not_cached() {
+        let mut cache = PcDecodeCache::new();
+        assert!(cache
+            .get_or_decode(0, |_| parse(&[0xff, 0xff, 0xff, 0xff]))
+            .is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cached_instructions_can_be_copied_out() {
+        let mut cache = PcDecodeCache::new();
+        let instruction = *cache
+            .get_or_decode(0, |pc| parse(&PROGRAM[pc as usize..]))
+            .unwrap();
+        cache.clear();
+        assert!(instruction.is_32bit());
+        assert_eq!(
+            instruction.operation,
+            Operation::ADDI {
+                imm: 1,
+                rs1: Register::A0,
+                rd: Register::A0,
+            }
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Provides names of the standard control and status registers.
+
+/// Returns the name of the standard CSR with number `csr`, or `None` if it is not a known
+/// user, supervisor or machine level CSR.
+pub fn csr_name(csr: u16) -> Option<&'static str> {
+    let name = match csr {
+        // User counters/timers
+        0xc00 => "cycle",
+        0xc01 => "time",
+        0xc02 => "instret",
+        0xc80 => "cycleh",
+        0xc81 => "timeh",
+        0xc82 => "instreth",
+        // Supervisor trap setup and handling
+        0x100 => "sstatus",
+        0x104 => "sie",
+        0x105 => "stvec",
+        0x106 => "scounteren",
+        0x140 => "sscratch",
+        0x141 => "sepc",
+        0x142 => "scause",
+        0x143 => "stval",
+        0x144 => "sip",
+        0x180 => "satp",
+        // Machine information registers
+        0xf11 => "mvendorid",
+        0xf12 => "marchid",
+        0xf13 => "mimpid",
+        0xf14 => "mhartid",
+        // Machine trap setup
+        0x300 => "mstatus",
+        0x301 => "misa",
+        0x302 => "medeleg",
+        0x303 => "mideleg",
+        0x304 => "mie",
+        0x305 => "mtvec",
+        0x306 => "mcounteren",
+        0x310 => "mstatush",
+        // Machine trap handling
+        0x340 => "mscratch",
+        0x341 => "mepc",
+        0x342 => "mcause",
+        0x343 => "mtval",
+        0x344 => "mip",
+        // Machine counters/timers
+        0xb00 => "mcycle",
+        0xb02 => "minstret",
+        0xb80 => "mcycleh",
+        0xb82 => "minstreth",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Returns the number of the standard CSR called `name`, the inverse of [`csr_name`].
+pub fn csr_number(name: &str) -> Option<u16> {
+    (0..0x1000).find(|&csr| csr_name(csr) == Some(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_csrs() {
+        assert_eq!(csr_name(0x300), Some("mstatus"));
+        assert_eq!(csr_name(0x301), Some("misa"));
+        assert_eq!(csr_name(0x305), Some("mtvec"));
+        assert_eq!(csr_name(0x341), Some("mepc"));
+        assert_eq!(csr_name(0x342), Some("mcause"));
+        assert_eq!(csr_name(0xc00), Some("cycle"));
+        assert_eq!(csr_name(0x7ff), None);
+        assert_eq!(csr_number("mstatus"), Some(0x300));
+        assert_eq!(csr_number("cycleh"), Some(0xc80));
+        assert_eq!(csr_number("x0"), None);
+    }
+}
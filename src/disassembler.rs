@@ -0,0 +1,338 @@
+//! Provides disassembly of whole code buffers.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+use crate::analysis::{collect_targets, for_each_instruction, static_target};
+use crate::error::ParseError;
+use crate::instructons::{instruction_length, Instruction, InstructionWidth, Offset, Operation};
+use crate::{decode_one, parse};
+
+/// Disassembles a code buffer one instruction at a time while keeping track of the address
+/// of each instruction.
+///
+/// Instructions that can not be parsed are skipped over using the width given by their first
+/// halfword. Iteration stops at the end of the buffer or at a trailing incomplete instruction.
+#[derive(Debug, Clone)]
+pub struct Disassembler<'a> {
+    bytes: &'a [u8],
+    base: u32,
+    offset: usize,
+}
+
+impl<'a> Disassembler<'a> {
+    /// Creates a disassembler for the code `bytes` loaded at address `base`.
+    pub fn new(bytes: &'a [u8], base: u32) -> Self {
+        Disassembler {
+            bytes,
+            base,
+            offset: 0,
+        }
+    }
+
+    /// Address of the next instruction.
+    pub fn pc(&self) -> u32 {
+        self.base.wrapping_add(self.offset as u32)
+    }
+}
+
+impl Iterator for Disassembler<'_> {
+    type Item = Result<(u32, Instruction), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.bytes[self.offset..];
+        let pc = self.pc();
+        match decode_one(rest) {
+            Ok((instruction, length)) => {
+                self.offset += length;
+                Some(Ok((pc, instruction)))
+            }
+            Err(ParseError::Truncated) => None,
+            Err(error) => {
+                // any other error means at least the first halfword is there
+                self.offset += instruction_length(<u16>::from_le_bytes([rest[0], rest[1]]));
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Formats the instruction located at `pc` as assembly, with the targets of conditional
+/// branches and `jal`s printed as absolute addresses, e.g. `beq a0, a1, 0x8004c`.
+pub fn format_instruction(pc: u32, instruction: &Instruction) -> String {
+    let text = instruction.to_assembly();
+    match static_target(&instruction.operation, pc) {
+        Some(target) => replace_target(&text, &format!("{:#x}", target)),
+        None => text,
+    }
+}
+
+/// Formats the instruction in the syntax of the GNU assembler, so that a listing can be
+/// reassembled. Compressed instructions are printed in their expanded form, the offsets of
+/// conditional branches and `jal`s relative to the location counter, e.g. `beq a0, a1, .+0x10`,
+/// and unknown encodings and fences with a reserved mode as `.half`/`.word` directives.
+/// [`parse_line`](crate::assembler::parse_line) parses the line back.
+pub fn gas_line(instruction: &Instruction) -> String {
+    let operation = &instruction.operation;
+    match operation {
+        Operation::Unknown { raw } => match instruction.width {
+            InstructionWidth::Bit32 => format!(".word {:#010x}", raw),
+            InstructionWidth::Bit16 => format!(".half {:#06x}", raw),
+        },
+        // there is no syntax for the reserved fence modes
+        Operation::FENCE { fm, .. } if *fm != 0 && operation.mnemonic() == "fence" => {
+            format!(".word {:#010x}", instruction.raw)
+        }
+        _ => match operation.branch_offset_bytes() {
+            Some(offset) if offset < 0 => {
+                replace_target(&operation.to_string(), &format!(".{}", Offset(offset)))
+            }
+            Some(offset) => {
+                replace_target(&operation.to_string(), &format!(".+{}", Offset(offset)))
+            }
+            None => operation.to_string(),
+        },
+    }
+}
+
+/// Decodes `word` located at `address` and formats it like `objdump -d` does, i.e. the address,
+/// the raw encoding and the disassembly with operands separated by bare commas:
+/// `   80000: 00b50533  add a0,a0,a1`. Only the low halfword of `word` is printed and decoded
+/// for compressed instructions.
+pub fn objdump_line(address: u32, word: u32) -> Result<String, ParseError> {
+    let instruction = parse(&word.to_le_bytes())?;
+    let raw = match instruction.width {
+        InstructionWidth::Bit32 => format!("{:08x}", word),
+        InstructionWidth::Bit16 => format!("{:04x}", word as u16),
+    };
+    Ok(format!(
+        "{:>8x}: {:<8}  {}",
+        address,
+        raw,
+        format_instruction(address, &instruction).replace(", ", ",")
+    ))
+}
+
+/// Layout of the raw encoding printed by [`hex_dump`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HexFormat {
+    /// The bytes in memory order, e.g. `b0 b5 af 02`.
+    Bytes,
+    /// The instruction as one number, e.g. `0x02afb5b0`.
+    Word,
+}
+
+/// Formats the raw encoding of the instruction, for printing next to its disassembly. Compressed
+/// instructions are printed as two bytes or a halfword.
+pub fn hex_dump(instruction: &Instruction, format: HexFormat) -> String {
+    let size = instruction.width.bytes() as usize;
+    match format {
+        HexFormat::Bytes => {
+            let mut dump = String::new();
+            for (i, byte) in instruction.raw.to_le_bytes()[..size].iter().enumerate() {
+                if i > 0 {
+                    dump.push(' ');
+                }
+                let _ = write!(dump, "{:02x}", byte);
+            }
+            dump
+        }
+        HexFormat::Word => format!("{:#0width$x}", instruction.raw, width = 2 + 2 * size),
+    }
+}
+
+/// Replaces the last operand, which is always the target for branches and jumps.
+fn replace_target(text: &str, target: &str) -> String {
+    match text.rsplit_once(", ") {
+        Some((operands, _)) => format!("{}, {}", operands, target),
+        // `c.jal` and `c.j` only have the target
+        None => match text.split_once(' ') {
+            Some((mnemonic, _)) => format!("{} {}", mnemonic, target),
+            None => String::from(text),
+        },
+    }
+}
+
+/// Disassembles the code `bytes` loaded at address `base` into a listing where the targets
+/// of conditional branches and `jal`s are replaced by generated labels (`.L0`, `.L1`, ...),
+/// which are defined in front of the instruction they point to. Targets that are not the start
+/// of an instruction in `bytes` keep their numeric offset.
+///
+/// Instructions that can not be parsed are emitted as `.half`/`.word` directives.
+pub fn disassemble_with_labels(bytes: &[u8], base: u32) -> String {
+    let mut starts = BTreeSet::new();
+    for_each_instruction(bytes, base, |pc, _| {
+        starts.insert(pc);
+    });
+    let labels: BTreeMap<u32, String> = collect_targets(bytes, base)
+        .intersection(&starts)
+        .enumerate()
+        .map(|(n, target)| (*target, format!(".L{}", n)))
+        .collect();
+
+    let mut listing = String::new();
+    let mut offset = 0;
+    for_each_instruction(bytes, base, |pc, instruction| {
+        if let Some(label) = labels.get(&pc) {
+            writeln!(listing, "{}:", label).unwrap();
+        }
+        match instruction {
+            Ok(instruction) => {
+                let text = instruction.to_assembly();
+                let text =
+                    match static_target(&instruction.operation, pc).and_then(|t| labels.get(&t)) {
+                        Some(label) => replace_target(&text, label),
+                        None => text,
+                    };
+                writeln!(listing, "\t{}", text).unwrap();
+                offset += instruction.width.bytes() as usize;
+            }
+            Err(_) => {
+                let halfword = <u16>::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+                if halfword & 0b11 == 0b11 {
+                    let word = <u32>::from_le_bytes([
+                        bytes[offset],
+                        bytes[offset + 1],
+                        bytes[offset + 2],
+                        bytes[offset + 3],
+                    ]);
+                    writeln!(listing, "\t.word {:#010x}", word).unwrap();
+                    offset += 4;
+                } else {
+                    writeln!(listing, "\t.half {:#06x}", halfword).unwrap();
+                    offset += 2;
+                }
+            }
+        }
+    });
+    listing
+}
+
+// every test here decodes compressed instructions
+#[cfg(all(test, feature = "ext-c"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loop_with_labels() {
+        let program = [
+            0x13, 0x05, 0xa0, 0x00, // 0x100: addi a0, zero, 10
+            0x13, 0x05, 0xf5, 0xff, // 0x104: addi a0, a0, -1
+            0xe3, 0x1e, 0x05, 0xfe, // 0x108: bnez a0, 0x104
+            0x21, 0x20, //             0x10c: c.jal 0x114
+            0xff, 0xff, 0xff, 0xff, // 0x10e: invalid
+            0x67, 0x80, 0x00, 0x00, // 0x112: ret
+        ];
+        assert_eq!(
+            disassemble_with_labels(&program, 0x100),
+            "\taddi a0, zero, 10\n\
+             .L0:\n\
+             \taddi a0, a0, -1\n\
+             \tbne a0, zero, .L0\n\
+             \tc.jal 0x8\n\
+             \t.word 0xffffffff\n\
+             \tjalr zero, 0(ra)\n"
+        );
+    }
+
+    #[test]
+    fn disassembler_tracks_pc() {
+        let program = [
+            0x63, 0x06, 0xb5, 0x00, // 0x80040: beq a0, a1, 0x8004c
+            0x05, 0x05, //             0x80044: c.addi a0, 1
+            0xff, 0xff, 0xff, 0xff, // 0x80046: invalid
+            0xef, 0xf0, 0x9f, 0xff, // 0x8004a: jal ra, 0x80042
+            0x13, //                   truncated
+        ];
+        let mut disassembler = Disassembler::new(&program, 0x80040);
+        let (pc, beq) = disassembler.next().unwrap().unwrap();
+        assert_eq!(pc, 0x80040);
+        assert_eq!(format_instruction(pc, &beq), "beq a0, a1, 0x8004c");
+        let (pc, addi) = disassembler.next().unwrap().unwrap();
+        assert_eq!(pc, 0x80044);
+        assert_eq!(format_instruction(pc, &addi), "c.addi a0, 1");
+        assert_eq!(disassembler.pc(), 0x80046);
+        assert!(disassembler.next().unwrap().is_err());
+        let (pc, jal) = disassembler.next().unwrap().unwrap();
+        assert_eq!(pc, 0x8004a);
+        assert_eq!(format_instruction(pc, &jal), "jal ra, 0x80042");
+        assert!(disassembler.next().is_none());
+    }
+
+    #[test]
+    fn hex_dumps() {
+        let lw = parse(&[0x03, 0x25, 0x81, 0x00]).unwrap();
+        assert_eq!(hex_dump(&lw, HexFormat::Bytes), "03 25 81 00");
+        assert_eq!(hex_dump(&lw, HexFormat::Word), "0x00812503");
+        let addi = parse(&[0x05, 0x05]).unwrap();
+        assert_eq!(hex_dump(&addi, HexFormat::Bytes), "05 05");
+        assert_eq!(hex_dump(&addi, HexFormat::Word), "0x0505");
+        let unknown = crate::parse_lenient(&[0xb0, 0xb5, 0xaf, 0x02]);
+        assert_eq!(hex_dump(&unknown, HexFormat::Bytes), "b0 b5");
+        let be = crate::parse_be(&[0x00, 0x81, 0x25, 0x03]).unwrap();
+        assert_eq!(hex_dump(&be, HexFormat::Bytes), "03 25 81 00");
+    }
+
+    #[test]
+    fn objdump_lines() {
+        assert_eq!(
+            objdump_line(0x80000, 0x00b50533).unwrap(),
+            "   80000: 00b50533  add a0,a0,a1"
+        );
+        assert_eq!(
+            objdump_line(0x80004, 0xff010113).unwrap(),
+            "   80004: ff010113  addi sp,sp,-16"
+        );
+        assert_eq!(
+            objdump_line(0x80008, 0x00112623).unwrap(),
+            "   80008: 00112623  sw ra,12(sp)"
+        );
+        assert_eq!(
+            objdump_line(0x8000c, 0xfe051ae3).unwrap(),
+            "   8000c: fe051ae3  bne a0,zero,0x80000"
+        );
+        assert_eq!(
+            objdump_line(0x80010, 0x0505).unwrap(),
+            "   80010: 0505      c.addi a0,1"
+        );
+        assert_eq!(
+            objdump_line(0x80012, 0x2011).unwrap(),
+            "   80012: 2011      c.jal 0x80016"
+        );
+        assert!(objdump_line(0x80014, 0xffffffff).is_err());
+    }
+
+    #[cfg(feature = "ext-f")]
+    #[test]
+    fn gas_lines() {
+        use crate::assembler::parse_line;
+
+        let lines: [(&[u8], &str); 9] = [
+            (&[0x63, 0x08, 0xb5, 0x00], "beq a0, a1, .+0x10"),
+            (&[0xe3, 0x1c, 0xb5, 0xfe], "bne a0, a1, .-0x8"),
+            (&[0x05, 0x05], "addi a0, a0, 1"),
+            (&[0x82, 0x80], "jalr zero, 0(ra)"),
+            (&[0x73, 0x95, 0x05, 0x30], "csrrw a0, mstatus, a1"),
+            (&[0x37, 0x55, 0x34, 0x12], "lui a0, 0x12345"),
+            (&[0x23, 0x2e, 0x11, 0xfe], "sw ra, -4(sp)"),
+            (&[0x53, 0x95, 0xc5, 0x00], "fadd.s fa0, fa1, fa2, rtz"),
+            (&[0x0f, 0x00, 0x00, 0x01], "pause"),
+        ];
+        for (bytes, line) in lines {
+            let instruction = parse(bytes).unwrap();
+            assert_eq!(gas_line(&instruction), line);
+            assert_eq!(parse_line(line), Ok(instruction.operation), "{}", line);
+        }
+        // the encodings without assembler syntax come back as their raw value
+        let unknown = crate::parse_lenient(&[0x7f, 0x70, 0x00, 0x00]);
+        assert_eq!(gas_line(&unknown), ".word 0x0000707f");
+        assert_eq!(
+            parse_line(".word 0x0000707f"),
+            Ok(Operation::Unknown { raw: 0x707f })
+        );
+        let fence = parse(&[0x0f, 0x00, 0xf0, 0x1f]).unwrap();
+        assert_eq!(gas_line(&fence), ".word 0x1ff0000f");
+    }
+}
@@ -0,0 +1,56 @@
+//! Provides disassembly of the `.text` section of ELF files. Requires the `elf` feature.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use object::{Object, ObjectSection};
+
+use crate::disassembler::Disassembler;
+use crate::error::ParseError;
+use crate::instructons::Instruction;
+
+/// Describes why an ELF file could not be disassembled.
+#[derive(Debug)]
+pub enum ElfError {
+    /// The file is not a valid ELF file.
+    Object(object::Error),
+    /// The file has no `.text` section.
+    MissingText,
+    /// An instruction in the `.text` section could not be parsed.
+    Parse(ParseError),
+}
+
+impl From<object::Error> for ElfError {
+    fn from(error: object::Error) -> Self {
+        ElfError::Object(error)
+    }
+}
+
+impl From<ParseError> for ElfError {
+    fn from(error: ParseError) -> Self {
+        ElfError::Parse(error)
+    }
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::Object(error) => write!(f, "Invalid ELF file: {}", error),
+            ElfError::MissingText => write!(f, "No .text section"),
+            ElfError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ElfError {}
+
+/// Disassembles the `.text` section of the ELF file `data`, returning every instruction with
+/// its virtual address. Fails on the first instruction that can not be parsed.
+pub fn disassemble_text(data: &[u8]) -> Result<Vec<(u32, Instruction)>, ElfError> {
+    let file = object::File::parse(data)?;
+    let text = file.section_by_name(".text").ok_or(ElfError::MissingText)?;
+    let instructions = Disassembler::new(text.data()?, text.address() as u32)
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    Ok(instructions)
+}
@@ -0,0 +1,1495 @@
+//! Provides encoding of operations back into their binary representation.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::instructons::Operation;
+use crate::registers::{FloatRegister, Register};
+use crate::rounding::RoundingMode;
+
+impl Operation {
+    /// Encodes the operation into its 32 bit binary representation.
+    /// Returns Err(String) if an immediate does not fit in its field.
+    pub fn encode(&self) -> Result<u32, String> {
+        match self {
+            Operation::LUI { rd, imm } => u_type(*imm, rd, 0b0110111),
+            Operation::AUIPC { rd, imm } => u_type(*imm, rd, 0b0010111),
+            Operation::JAL { rd, imm } => j_type(*imm, rd),
+            Operation::JALR { rd, rs1, imm } => i_type(*imm, rs1, 0b000, rd, 0b1100111),
+            Operation::BEQ { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b000),
+            Operation::BNE { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b001),
+            Operation::BLT { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b100),
+            Operation::BGE { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b101),
+            Operation::BLTU { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b110),
+            Operation::BGEU { imm, rs1, rs2 } => b_type(*imm, rs2, rs1, 0b111),
+            Operation::LB { imm, rs1, rd } => i_type(*imm, rs1, 0b000, rd, 0b0000011),
+            Operation::LH { imm, rs1, rd } => i_type(*imm, rs1, 0b001, rd, 0b0000011),
+            Operation::LW { imm, rs1, rd } => i_type(*imm, rs1, 0b010, rd, 0b0000011),
+            Operation::LBU { imm, rs1, rd } => i_type(*imm, rs1, 0b100, rd, 0b0000011),
+            Operation::LHU { imm, rs1, rd } => i_type(*imm, rs1, 0b101, rd, 0b0000011),
+            Operation::SB { imm, rs2, rs1 } => s_type(*imm, rs2, rs1, 0b000),
+            Operation::SH { imm, rs2, rs1 } => s_type(*imm, rs2, rs1, 0b001),
+            Operation::SW { imm, rs2, rs1 } => s_type(*imm, rs2, rs1, 0b010),
+            Operation::ADDI { imm, rs1, rd } => i_type(*imm, rs1, 0b000, rd, 0b0010011),
+            Operation::SLTI { imm, rs1, rd } => i_type(*imm, rs1, 0b010, rd, 0b0010011),
+            Operation::SLTIU { imm, rs1, rd } => i_type(*imm, rs1, 0b011, rd, 0b0010011),
+            Operation::XORI { imm, rs1, rd } => i_type(*imm, rs1, 0b100, rd, 0b0010011),
+            Operation::ORI { imm, rs1, rd } => i_type(*imm, rs1, 0b110, rd, 0b0010011),
+            Operation::ANDI { imm, rs1, rd } => i_type(*imm, rs1, 0b111, rd, 0b0010011),
+            Operation::SLLI { shamt, rs1, rd } => {
+                shift(0b0000000, *shamt, 6, rs1, 0b001, rd, 0b0010011)
+            }
+            Operation::SRLI { shamt, rs1, rd } => {
+                shift(0b0000000, *shamt, 6, rs1, 0b101, rd, 0b0010011)
+            }
+            Operation::SRAI { shamt, rs1, rd } => {
+                shift(0b0100000, *shamt, 6, rs1, 0b101, rd, 0b0010011)
+            }
+            Operation::ADD { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b000, rd, 0b0110011))
+            }
+            Operation::SUB { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b000, rd, 0b0110011))
+            }
+            Operation::SLL { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::SLT { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b010, rd, 0b0110011))
+            }
+            Operation::SLTU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b011, rd, 0b0110011))
+            }
+            Operation::XOR { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b100, rd, 0b0110011))
+            }
+            Operation::SRL { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::SRA { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::OR { rs2, rs1, rd } => Ok(r_type(0b0000000, rs2, rs1, 0b110, rd, 0b0110011)),
+            Operation::AND { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b111, rd, 0b0110011))
+            }
+            Operation::MUL { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b000, rd, 0b0110011))
+            }
+            Operation::MULH { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::MULHSU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b010, rd, 0b0110011))
+            }
+            Operation::MULHU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b011, rd, 0b0110011))
+            }
+            Operation::DIV { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b100, rd, 0b0110011))
+            }
+            Operation::DIVU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::REM { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b110, rd, 0b0110011))
+            }
+            Operation::REMU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000001, rs2, rs1, 0b111, rd, 0b0110011))
+            }
+            Operation::LWU { imm, rs1, rd } => i_type(*imm, rs1, 0b110, rd, 0b0000011),
+            Operation::LD { imm, rs1, rd } => i_type(*imm, rs1, 0b011, rd, 0b0000011),
+            Operation::SD { imm, rs2, rs1 } => s_type(*imm, rs2, rs1, 0b011),
+            Operation::ADDIW { imm, rs1, rd } => i_type(*imm, rs1, 0b000, rd, 0b0011011),
+            Operation::SLLIW { shamt, rs1, rd } => {
+                shift(0b0000000, *shamt, 5, rs1, 0b001, rd, 0b0011011)
+            }
+            Operation::SRLIW { shamt, rs1, rd } => {
+                shift(0b0000000, *shamt, 5, rs1, 0b101, rd, 0b0011011)
+            }
+            Operation::SRAIW { shamt, rs1, rd } => {
+                shift(0b0100000, *shamt, 5, rs1, 0b101, rd, 0b0011011)
+            }
+            Operation::ADDW { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b000, rd, 0b0111011))
+            }
+            Operation::SUBW { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b000, rd, 0b0111011))
+            }
+            Operation::SLLW { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b001, rd, 0b0111011))
+            }
+            Operation::SRLW { rs2, rs1, rd } => {
+                Ok(r_type(0b0000000, rs2, rs1, 0b101, rd, 0b0111011))
+            }
+            Operation::SRAW { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b101, rd, 0b0111011))
+            }
+            Operation::CLMUL { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::CLMULH { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b011, rd, 0b0110011))
+            }
+            Operation::CLMULR { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b010, rd, 0b0110011))
+            }
+            Operation::SH1ADD { rs2, rs1, rd } => {
+                Ok(r_type(0b0010000, rs2, rs1, 0b010, rd, 0b0110011))
+            }
+            Operation::SH2ADD { rs2, rs1, rd } => {
+                Ok(r_type(0b0010000, rs2, rs1, 0b100, rd, 0b0110011))
+            }
+            Operation::SH3ADD { rs2, rs1, rd } => {
+                Ok(r_type(0b0010000, rs2, rs1, 0b110, rd, 0b0110011))
+            }
+            Operation::ANDN { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b111, rd, 0b0110011))
+            }
+            Operation::ORN { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b110, rd, 0b0110011))
+            }
+            Operation::XNOR { rs2, rs1, rd } => {
+                Ok(r_type(0b0100000, rs2, rs1, 0b100, rd, 0b0110011))
+            }
+            Operation::MIN { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b100, rd, 0b0110011))
+            }
+            Operation::MINU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::MAX { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b110, rd, 0b0110011))
+            }
+            Operation::MAXU { rs2, rs1, rd } => {
+                Ok(r_type(0b0000101, rs2, rs1, 0b111, rd, 0b0110011))
+            }
+            Operation::ROL { rs2, rs1, rd } => {
+                Ok(r_type(0b0110000, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::ROR { rs2, rs1, rd } => {
+                Ok(r_type(0b0110000, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::BCLR { rs2, rs1, rd } => {
+                Ok(r_type(0b0100100, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::BEXT { rs2, rs1, rd } => {
+                Ok(r_type(0b0100100, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::BINV { rs2, rs1, rd } => {
+                Ok(r_type(0b0110100, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::BSET { rs2, rs1, rd } => {
+                Ok(r_type(0b0010100, rs2, rs1, 0b001, rd, 0b0110011))
+            }
+            Operation::CZERO_EQZ { rs2, rs1, rd } => {
+                Ok(r_type(0b0000111, rs2, rs1, 0b101, rd, 0b0110011))
+            }
+            Operation::CZERO_NEZ { rs2, rs1, rd } => {
+                Ok(r_type(0b0000111, rs2, rs1, 0b111, rd, 0b0110011))
+            }
+            Operation::RORI { shamt, rs1, rd } => {
+                shift(0b0110000, *shamt, 6, rs1, 0b101, rd, 0b0010011)
+            }
+            Operation::BCLRI { shamt, rs1, rd } => {
+                shift(0b0100100, *shamt, 6, rs1, 0b001, rd, 0b0010011)
+            }
+            Operation::BEXTI { shamt, rs1, rd } => {
+                shift(0b0100100, *shamt, 6, rs1, 0b101, rd, 0b0010011)
+            }
+            Operation::BINVI { shamt, rs1, rd } => {
+                shift(0b0110100, *shamt, 6, rs1, 0b001, rd, 0b0010011)
+            }
+            Operation::BSETI { shamt, rs1, rd } => {
+                shift(0b0010100, *shamt, 6, rs1, 0b001, rd, 0b0010011)
+            }
+            Operation::CLZ { rs1, rd } => i_type(0x600, rs1, 0b001, rd, 0b0010011),
+            Operation::CTZ { rs1, rd } => i_type(0x601, rs1, 0b001, rd, 0b0010011),
+            Operation::CPOP { rs1, rd } => i_type(0x602, rs1, 0b001, rd, 0b0010011),
+            Operation::SEXT_B { rs1, rd } => i_type(0x604, rs1, 0b001, rd, 0b0010011),
+            Operation::SEXT_H { rs1, rd } => i_type(0x605, rs1, 0b001, rd, 0b0010011),
+            Operation::REV8 { rs1, rd } => i_type(0x698, rs1, 0b101, rd, 0b0010011),
+            Operation::ORC_B { rs1, rd } => i_type(0x287, rs1, 0b101, rd, 0b0010011),
+            Operation::ZEXT_H { rs1, rd } => Ok(r_type(
+                0b0000100,
+                &Register::ZERO,
+                rs1,
+                0b100,
+                rd,
+                0b0110011,
+            )),
+            Operation::FENCE { fm, pred, succ } => {
+                if *fm > 0b1111 || *pred > 0b1111 || *succ > 0b1111 {
+                    return Err(format!(
+                        "Fence fields fm {:#x}, pred {:#x} and succ {:#x} do not fit in 4 bits",
+                        fm, pred, succ
+                    ));
+                }
+                Ok(((*fm as u32) << 28)
+                    | ((*pred as u32) << 24)
+                    | ((*succ as u32) << 20)
+                    | 0b0001111)
+            }
+            // fence w, 0
+            Operation::PAUSE {} => Ok(0x0100000f),
+            Operation::FENCE_I {} => Ok(0x0000100f),
+            Operation::ECALL {} => Ok(0x00000073),
+            Operation::EBREAK {} => Ok(0x00100073),
+            Operation::MRET {} => Ok(0x30200073),
+            Operation::SFENCE_VMA { rs1, rs2 } => Ok(r_type(
+                0b0001001,
+                rs2,
+                rs1,
+                0b000,
+                &Register::ZERO,
+                0b1110011,
+            )),
+            Operation::CSRRW { csr, rs1, rd } => csr_type(*csr, *rs1 as u32, 0b001, rd),
+            Operation::CSRRS { csr, rs1, rd } => csr_type(*csr, *rs1 as u32, 0b010, rd),
+            Operation::CSRRC { csr, rs1, rd } => csr_type(*csr, *rs1 as u32, 0b011, rd),
+            Operation::CSRRWI { csr, zimm, rd } => csr_type(*csr, zimm_field(*zimm)?, 0b101, rd),
+            Operation::CSRRSI { csr, zimm, rd } => csr_type(*csr, zimm_field(*zimm)?, 0b110, rd),
+            Operation::CSRRCI { csr, zimm, rd } => csr_type(*csr, zimm_field(*zimm)?, 0b111, rd),
+            Operation::LR_W { rs1, rd, aq, rl } => {
+                Ok(amo_type(0b00010, *aq, *rl, &Register::ZERO, rs1, rd))
+            }
+            Operation::SC_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b00011, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOSWAP_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b00001, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOADD_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b00000, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOXOR_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b00100, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOAND_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b01100, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOOR_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b01000, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOMIN_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b10000, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOMAX_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b10100, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOMINU_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b11000, *aq, *rl, rs2, rs1, rd)),
+            Operation::AMOMAXU_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => Ok(amo_type(0b11100, *aq, *rl, rs2, rs1, rd)),
+            Operation::FLW { imm, rs1, rd } => fp_load(*imm, rs1, rd),
+            Operation::FSW { imm, rs2, rs1 } => fp_store(*imm, rs2, rs1),
+            Operation::FADD_S { rs2, rs1, rd, rm } => {
+                Ok(fp_type(0b0000000, *rs2 as u32, rs1, rm, rd))
+            }
+            Operation::FSUB_S { rs2, rs1, rd, rm } => {
+                Ok(fp_type(0b0000100, *rs2 as u32, rs1, rm, rd))
+            }
+            Operation::FMUL_S { rs2, rs1, rd, rm } => {
+                Ok(fp_type(0b0001000, *rs2 as u32, rs1, rm, rd))
+            }
+            Operation::FDIV_S { rs2, rs1, rd, rm } => {
+                Ok(fp_type(0b0001100, *rs2 as u32, rs1, rm, rd))
+            }
+            Operation::FSQRT_S { rs1, rd, rm } => Ok(fp_type(0b0101100, 0, rs1, rm, rd)),
+            Operation::FMADD_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => Ok(r4_type(rs3, rs2, rs1, rm, rd, 0b1000011)),
+            Operation::FMSUB_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => Ok(r4_type(rs3, rs2, rs1, rm, rd, 0b1000111)),
+            Operation::FNMSUB_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => Ok(r4_type(rs3, rs2, rs1, rm, rd, 0b1001011)),
+            Operation::FNMADD_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => Ok(r4_type(rs3, rs2, rs1, rm, rd, 0b1001111)),
+            Operation::Unknown { raw } => Ok(*raw),
+        }
+    }
+}
+
+/// Returns one example encoding for every operation that can be parsed, together with its
+/// mnemonic. Useful as fuzzing seeds or for documentation tables.
+pub fn all_example_encodings() -> Vec<(&'static str, u32)> {
+    vec![
+        ("lui", 0x12345537),        // lui a0, 0x12345
+        ("auipc", 0x12345517),      // auipc a0, 0x12345
+        ("jal", 0x010000ef),        // jal ra, 16
+        ("jalr", 0xff8500e7),       // jalr ra, -8(a0)
+        ("beq", 0x00b50863),        // beq a0, a1, 16
+        ("bne", 0xfeb51ce3),        // bne a0, a1, -8
+        ("blt", 0x00b54863),        // blt a0, a1, 16
+        ("bge", 0x00b55863),        // bge a0, a1, 16
+        ("bltu", 0x00b56863),       // bltu a0, a1, 16
+        ("bgeu", 0x00b57863),       // bgeu a0, a1, 16
+        ("lb", 0xffc10503),         // lb a0, -4(sp)
+        ("lh", 0xffc11503),         // lh a0, -4(sp)
+        ("lw", 0x00812503),         // lw a0, 8(sp)
+        ("lbu", 0xffc14503),        // lbu a0, -4(sp)
+        ("lhu", 0xffc15503),        // lhu a0, -4(sp)
+        ("sb", 0xfe110e23),         // sb ra, -4(sp)
+        ("sh", 0xfe111e23),         // sh ra, -4(sp)
+        ("sw", 0xfe112e23),         // sw ra, -4(sp)
+        ("addi", 0xfff50513),       // addi a0, a0, -1
+        ("slti", 0xffb52513),       // slti a0, a0, -5
+        ("sltiu", 0x00153513),      // sltiu a0, a0, 1
+        ("xori", 0xfff54513),       // xori a0, a0, -1
+        ("ori", 0x0ff56513),        // ori a0, a0, 255
+        ("andi", 0x0ff57513),       // andi a0, a0, 255
+        ("slli", 0x00351513),       // slli a0, a0, 3
+        ("srli", 0x00355513),       // srli a0, a0, 3
+        ("srai", 0x40355513),       // srai a0, a0, 3
+        ("add", 0x00b50533),        // add a0, a0, a1
+        ("sub", 0x40b50533),        // sub a0, a0, a1
+        ("sll", 0x00b51533),        // sll a0, a0, a1
+        ("slt", 0x00b52533),        // slt a0, a0, a1
+        ("sltu", 0x00b53533),       // sltu a0, a0, a1
+        ("xor", 0x00b54533),        // xor a0, a0, a1
+        ("srl", 0x00b55533),        // srl a0, a0, a1
+        ("sra", 0x40b55533),        // sra a0, a0, a1
+        ("or", 0x00b56533),         // or a0, a0, a1
+        ("and", 0x00b57533),        // and a0, a0, a1
+        ("mul", 0x02c58533),        // mul a0, a1, a2
+        ("mulh", 0x02c59533),       // mulh a0, a1, a2
+        ("mulhsu", 0x02c5a533),     // mulhsu a0, a1, a2
+        ("mulhu", 0x02c5b533),      // mulhu a0, a1, a2
+        ("div", 0x02c5c533),        // div a0, a1, a2
+        ("divu", 0x02c5d533),       // divu a0, a1, a2
+        ("rem", 0x02c5e533),        // rem a0, a1, a2
+        ("remu", 0x02c5f533),       // remu a0, a1, a2
+        ("clmul", 0x0ac59533),      // clmul a0, a1, a2
+        ("clmulh", 0x0ac5b533),     // clmulh a0, a1, a2
+        ("clmulr", 0x0ac5a533),     // clmulr a0, a1, a2
+        ("sh1add", 0x20c5a533),     // sh1add a0, a1, a2
+        ("sh2add", 0x20c5c533),     // sh2add a0, a1, a2
+        ("sh3add", 0x20c5e533),     // sh3add a0, a1, a2
+        ("andn", 0x40c5f533),       // andn a0, a1, a2
+        ("orn", 0x40c5e533),        // orn a0, a1, a2
+        ("xnor", 0x40c5c533),       // xnor a0, a1, a2
+        ("min", 0x0ac5c533),        // min a0, a1, a2
+        ("minu", 0x0ac5d533),       // minu a0, a1, a2
+        ("max", 0x0ac5e533),        // max a0, a1, a2
+        ("maxu", 0x0ac5f533),       // maxu a0, a1, a2
+        ("rol", 0x60c59533),        // rol a0, a1, a2
+        ("ror", 0x60c5d533),        // ror a0, a1, a2
+        ("bclr", 0x48c59533),       // bclr a0, a1, a2
+        ("bext", 0x48c5d533),       // bext a0, a1, a2
+        ("binv", 0x68c59533),       // binv a0, a1, a2
+        ("bset", 0x28c59533),       // bset a0, a1, a2
+        ("czero.eqz", 0x0ec5d533),  // czero.eqz a0, a1, a2
+        ("czero.nez", 0x0ec5f533),  // czero.nez a0, a1, a2
+        ("rori", 0x6075d513),       // rori a0, a1, 7
+        ("bclri", 0x48759513),      // bclri a0, a1, 7
+        ("bexti", 0x4875d513),      // bexti a0, a1, 7
+        ("binvi", 0x68759513),      // binvi a0, a1, 7
+        ("bseti", 0x28759513),      // bseti a0, a1, 7
+        ("clz", 0x60059513),        // clz a0, a1
+        ("ctz", 0x60159513),        // ctz a0, a1
+        ("cpop", 0x60259513),       // cpop a0, a1
+        ("sext.b", 0x60459513),     // sext.b a0, a1
+        ("sext.h", 0x60559513),     // sext.h a0, a1
+        ("rev8", 0x6985d513),       // rev8 a0, a1
+        ("orc.b", 0x2875d513),      // orc.b a0, a1
+        ("zext.h", 0x0805c533),     // zext.h a0, a1
+        ("ecall", 0x00000073),      // ecall
+        ("ebreak", 0x00100073),     // ebreak
+        ("mret", 0x30200073),       // mret
+        ("sfence.vma", 0x12b50073), // sfence.vma a0, a1
+        ("csrrw", 0x30059573),      // csrrw a0, mstatus, a1
+        ("csrrs", 0x3005a573),      // csrrs a0, mstatus, a1
+        ("csrrc", 0x3005b573),      // csrrc a0, mstatus, a1
+        ("csrrwi", 0x3002d573),     // csrrwi a0, mstatus, 5
+        ("csrrsi", 0x3002e573),     // csrrsi a0, mstatus, 5
+        ("csrrci", 0x3002f573),     // csrrci a0, mstatus, 5
+        ("lr.w", 0x1005a52f),       // lr.w a0, (a1)
+        ("sc.w", 0x18c5a52f),       // sc.w a0, a2, (a1)
+        ("amoswap.w", 0x08c5a52f),  // amoswap.w a0, a2, (a1)
+        ("amoadd.w", 0x00c5a52f),   // amoadd.w a0, a2, (a1)
+        ("amoxor.w", 0x20c5a52f),   // amoxor.w a0, a2, (a1)
+        ("amoand.w", 0x60c5a52f),   // amoand.w a0, a2, (a1)
+        ("amoor.w", 0x40c5a52f),    // amoor.w a0, a2, (a1)
+        ("amomin.w", 0x80c5a52f),   // amomin.w a0, a2, (a1)
+        ("amomax.w", 0xa0c5a52f),   // amomax.w a0, a2, (a1)
+        ("amominu.w", 0xc0c5a52f),  // amominu.w a0, a2, (a1)
+        ("amomaxu.w", 0xe0c5a52f),  // amomaxu.w a0, a2, (a1)
+        ("fence", 0x0ff0000f),      // fence iorw, iorw
+        ("fence.tso", 0x8330000f),  // fence.tso
+        ("fence.i", 0x0000100f),    // fence.i
+        ("pause", 0x0100000f),      // pause
+        ("flw", 0x00812507),        // flw fa0, 8(sp)
+        ("fsw", 0xfea12e27),        // fsw fa0, -4(sp)
+        ("fadd.s", 0x00c5f553),     // fadd.s fa0, fa1, fa2
+        ("fsub.s", 0x08c5f553),     // fsub.s fa0, fa1, fa2
+        ("fmul.s", 0x10c5f553),     // fmul.s fa0, fa1, fa2
+        ("fdiv.s", 0x18c5f553),     // fdiv.s fa0, fa1, fa2
+        ("fsqrt.s", 0x5805f553),    // fsqrt.s fa0, fa1
+        ("fmadd.s", 0x68c5f543),    // fmadd.s fa0, fa1, fa2, fa3
+        ("fmsub.s", 0x68c5f547),    // fmsub.s fa0, fa1, fa2, fa3
+        ("fnmsub.s", 0x68c5f54b),   // fnmsub.s fa0, fa1, fa2, fa3
+        ("fnmadd.s", 0x68c5f54f),   // fnmadd.s fa0, fa1, fa2, fa3
+    ]
+}
+
+/// Checks that `imm` is representable as a signed `bits` wide value.
+fn check_signed(imm: i32, bits: u32) -> Result<(), String> {
+    let limit = 1i32 << (bits - 1);
+    if (-limit..limit).contains(&imm) {
+        Ok(())
+    } else {
+        Err(format!("Immediate {} does not fit in {} bits", imm, bits))
+    }
+}
+
+/// Checks that a pc-relative offset is a multiple of two.
+fn check_aligned(imm: i32) -> Result<(), String> {
+    if imm & 0b1 == 0 {
+        Ok(())
+    } else {
+        Err(format!("Offset {} is not a multiple of 2", imm))
+    }
+}
+
+fn r_type(
+    funct7: u32,
+    rs2: &Register,
+    rs1: &Register,
+    funct3: u32,
+    rd: &Register,
+    opcode: u32,
+) -> u32 {
+    (funct7 << 25)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((*rd as u32) << 7)
+        | opcode
+}
+
+fn i_type(
+    imm: i32,
+    rs1: &Register,
+    funct3: u32,
+    rd: &Register,
+    opcode: u32,
+) -> Result<u32, String> {
+    check_signed(imm, 12)?;
+    Ok(((imm as u32 & 0xfff) << 20)
+        | ((*rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((*rd as u32) << 7)
+        | opcode)
+}
+
+/// The shift amount is `bits` wide: 6 bits for the RV64 forms of `slli`, `srli` and `srai`,
+/// 5 bits for the word shifts.
+fn shift(
+    funct7: u32,
+    shamt: u8,
+    bits: u32,
+    rs1: &Register,
+    funct3: u32,
+    rd: &Register,
+    opcode: u32,
+) -> Result<u32, String> {
+    if shamt as u32 >= 1 << bits {
+        return Err(format!(
+            "Shift amount {} does not fit in {} bits",
+            shamt, bits
+        ));
+    }
+    Ok((funct7 << 25)
+        | ((shamt as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((*rd as u32) << 7)
+        | opcode)
+}
+
+fn s_type(imm: i32, rs2: &Register, rs1: &Register, funct3: u32) -> Result<u32, String> {
+    check_signed(imm, 12)?;
+    let imm = imm as u32;
+    Ok(((imm >> 5) << 25)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (funct3 << 12)
+        | ((imm & 0b11111) << 7)
+        | 0b0100011)
+}
+
+fn b_type(imm: i32, rs2: &Register, rs1: &Register, funct3: u32) -> Result<u32, String> {
+    check_signed(imm, 13)?;
+    check_aligned(imm)?;
+    let imm = imm as u32;
+    Ok((((imm >> 12) & 0b1) << 31)
+        | (((imm >> 5) & 0b111111) << 25)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (funct3 << 12)
+        | (((imm >> 1) & 0b1111) << 8)
+        | (((imm >> 11) & 0b1) << 7)
+        | 0b1100011)
+}
+
+fn u_type(imm: i32, rd: &Register, opcode: u32) -> Result<u32, String> {
+    if imm & 0xfff != 0 {
+        return Err(format!("Immediate {:#x} has nonzero low 12 bits", imm));
+    }
+    Ok(imm as u32 | ((*rd as u32) << 7) | opcode)
+}
+
+fn j_type(imm: i32, rd: &Register) -> Result<u32, String> {
+    check_signed(imm, 21)?;
+    check_aligned(imm)?;
+    let imm = imm as u32;
+    Ok((((imm >> 20) & 0b1) << 31)
+        | (((imm >> 1) & 0b1111111111) << 21)
+        | (((imm >> 11) & 0b1) << 20)
+        | (imm & (0b11111111 << 12))
+        | ((*rd as u32) << 7)
+        | 0b1101111)
+}
+
+fn csr_type(csr: u16, source: u32, funct3: u32, rd: &Register) -> Result<u32, String> {
+    if csr >= 1 << 12 {
+        return Err(format!("CSR {:#x} does not fit in 12 bits", csr));
+    }
+    Ok(((csr as u32) << 20) | (source << 15) | (funct3 << 12) | ((*rd as u32) << 7) | 0b1110011)
+}
+
+fn zimm_field(zimm: u8) -> Result<u32, String> {
+    if zimm >= 32 {
+        return Err(format!("Immediate {} does not fit in 5 bits", zimm));
+    }
+    Ok(zimm as u32)
+}
+
+fn amo_type(funct5: u32, aq: bool, rl: bool, rs2: &Register, rs1: &Register, rd: &Register) -> u32 {
+    (funct5 << 27)
+        | ((aq as u32) << 26)
+        | ((rl as u32) << 25)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (0b010 << 12)
+        | ((*rd as u32) << 7)
+        | 0b0101111
+}
+
+/// `flw`, an I-type load into a floating-point register.
+fn fp_load(imm: i32, rs1: &Register, rd: &FloatRegister) -> Result<u32, String> {
+    check_signed(imm, 12)?;
+    Ok(((imm as u32 & 0xfff) << 20)
+        | ((*rs1 as u32) << 15)
+        | (0b010 << 12)
+        | ((*rd as u32) << 7)
+        | 0b0000111)
+}
+
+/// `fsw`, an S-type store from a floating-point register.
+fn fp_store(imm: i32, rs2: &FloatRegister, rs1: &Register) -> Result<u32, String> {
+    check_signed(imm, 12)?;
+    let imm = imm as u32;
+    Ok(((imm >> 5) << 25)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | (0b010 << 12)
+        | ((imm & 0b11111) << 7)
+        | 0b0100111)
+}
+
+/// Floating-point operation with the rounding mode in the funct3 field.
+fn fp_type(
+    funct7: u32,
+    rs2: u32,
+    rs1: &FloatRegister,
+    rm: &RoundingMode,
+    rd: &FloatRegister,
+) -> u32 {
+    (funct7 << 25)
+        | (rs2 << 20)
+        | ((*rs1 as u32) << 15)
+        | ((*rm as u32) << 12)
+        | ((*rd as u32) << 7)
+        | 0b1010011
+}
+
+/// Fused multiply-add with the third source register in the top five bits. The two bits
+/// below it select the format, which is always single precision.
+fn r4_type(
+    rs3: &FloatRegister,
+    rs2: &FloatRegister,
+    rs1: &FloatRegister,
+    rm: &RoundingMode,
+    rd: &FloatRegister,
+    opcode: u32,
+) -> u32 {
+    ((*rs3 as u32) << 27)
+        | ((*rs2 as u32) << 20)
+        | ((*rs1 as u32) << 15)
+        | ((*rm as u32) << 12)
+        | ((*rd as u32) << 7)
+        | opcode
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    use crate::assembler::parse_line;
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    use crate::disassembler::gas_line;
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    use crate::isa::{IsaConfig, Xlen};
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    use crate::parse_with_config;
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    use alloc::string::ToString;
+
+    #[test]
+    fn immediate_out_of_range() {
+        let addi = Operation::ADDI {
+            imm: 2048,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert!(addi.encode().is_err());
+        let addi = Operation::ADDI {
+            imm: -2048,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(addi.encode(), Ok(0x80050513));
+        let beq = Operation::BEQ {
+            imm: 3,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert!(beq.encode().is_err());
+        let jal = Operation::JAL {
+            rd: Register::RA,
+            imm: 1 << 20,
+        };
+        assert!(jal.encode().is_err());
+        let lui = Operation::LUI {
+            rd: Register::A0,
+            imm: 0x123,
+        };
+        assert!(lui.encode().is_err());
+    }
+
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    #[test]
+    fn example_encodings() {
+        for (mnemonic, word) in all_example_encodings() {
+            let operation = crate::parse(&word.to_le_bytes()).unwrap().operation;
+            let text = operation.to_string();
+            assert_eq!(operation.mnemonic(), mnemonic, "{:#010x}", word);
+            assert_eq!(text.split(' ').next(), Some(mnemonic), "{:#010x}", word);
+            assert_eq!(operation.encode(), Ok(word), "{}", text);
+            assert_eq!(parse_line(&text), Ok(operation), "{}", text);
+        }
+    }
+
+    #[test]
+    fn fixed_encodings() {
+        assert_eq!(Operation::ECALL {}.encode(), Ok(0x00000073));
+        assert_eq!(Operation::EBREAK {}.encode(), Ok(0x00100073));
+        assert_eq!(Operation::MRET {}.encode(), Ok(0x30200073));
+    }
+
+    /// Xorshift generator, so that the round trip test is reproducible without depending on a
+    /// property testing crate.
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    struct Rng(u32);
+
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    impl Rng {
+        fn next(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn register(&mut self) -> Register {
+            ((self.next() % 32) as u8).try_into().unwrap()
+        }
+
+        fn float_register(&mut self) -> FloatRegister {
+            ((self.next() % 32) as u8).try_into().unwrap()
+        }
+
+        fn rounding_mode(&mut self) -> RoundingMode {
+            [
+                RoundingMode::Rne,
+                RoundingMode::Rtz,
+                RoundingMode::Rdn,
+                RoundingMode::Rup,
+                RoundingMode::Rmm,
+                RoundingMode::Dyn,
+            ][(self.next() % 6) as usize]
+        }
+
+        /// A random immediate that fits in a signed `bits` wide field.
+        fn signed(&mut self, bits: u32) -> i32 {
+            ((self.next() << (32 - bits)) as i32) >> (32 - bits)
+        }
+
+        /// A random shift amount that fits in `bits` bits.
+        fn shamt(&mut self, bits: u32) -> u8 {
+            (self.next() % (1 << bits)) as u8
+        }
+    }
+
+    /// Operations of every RV32 format with random operands that fit their fields, covering the
+    /// base set, M, A, F, Zba, Zbb, Zbs, Zbc and Zicond.
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    fn random_operations(rng: &mut Rng) -> Vec<Operation> {
+        vec![
+            Operation::LUI {
+                rd: rng.register(),
+                imm: rng.signed(20) << 12,
+            },
+            Operation::AUIPC {
+                rd: rng.register(),
+                imm: rng.signed(20) << 12,
+            },
+            Operation::JAL {
+                rd: rng.register(),
+                imm: rng.signed(21) & !0b1,
+            },
+            Operation::JALR {
+                rd: rng.register(),
+                rs1: rng.register(),
+                imm: rng.signed(12),
+            },
+            Operation::BEQ {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::BNE {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::BLT {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::BGE {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::BLTU {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::BGEU {
+                imm: rng.signed(13) & !0b1,
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::LB {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::LH {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::LW {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::LBU {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::LHU {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SB {
+                imm: rng.signed(12),
+                rs2: rng.register(),
+                rs1: rng.register(),
+            },
+            Operation::SH {
+                imm: rng.signed(12),
+                rs2: rng.register(),
+                rs1: rng.register(),
+            },
+            Operation::SW {
+                imm: rng.signed(12),
+                rs2: rng.register(),
+                rs1: rng.register(),
+            },
+            Operation::ADDI {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLTI {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLTIU {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::XORI {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ORI {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ANDI {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLLI {
+                shamt: (rng.next() % 32) as u8,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRLI {
+                shamt: (rng.next() % 32) as u8,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRAI {
+                shamt: (rng.next() % 32) as u8,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ADD {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SUB {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLL {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLT {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLTU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::XOR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRL {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRA {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::OR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::AND {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MUL {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MULH {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MULHSU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MULHU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::DIV {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::DIVU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::REM {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::REMU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CSRRW {
+                csr: (rng.next() % 4096) as u16,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CSRRS {
+                csr: (rng.next() % 4096) as u16,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CSRRC {
+                csr: (rng.next() % 4096) as u16,
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CSRRWI {
+                csr: (rng.next() % 4096) as u16,
+                zimm: (rng.next() % 32) as u8,
+                rd: rng.register(),
+            },
+            Operation::CSRRSI {
+                csr: (rng.next() % 4096) as u16,
+                zimm: (rng.next() % 32) as u8,
+                rd: rng.register(),
+            },
+            Operation::CSRRCI {
+                csr: (rng.next() % 4096) as u16,
+                zimm: (rng.next() % 32) as u8,
+                rd: rng.register(),
+            },
+            Operation::LR_W {
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::SC_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOSWAP_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOADD_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOXOR_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOAND_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOOR_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOMIN_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOMAX_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOMINU_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::AMOMAXU_W {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+                aq: rng.next() & 1 == 1,
+                rl: rng.next() & 1 == 1,
+            },
+            Operation::FENCE {
+                fm: (rng.next() % 16) as u8,
+                pred: (rng.next() % 16) as u8,
+                // succ 0 with pred w would be pause
+                succ: (rng.next() % 15 + 1) as u8,
+            },
+            Operation::SFENCE_VMA {
+                rs1: rng.register(),
+                rs2: rng.register(),
+            },
+            Operation::CLMUL {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CLMULH {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CLMULR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SH1ADD {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SH2ADD {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SH3ADD {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ANDN {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ORN {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::XNOR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MIN {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MINU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MAX {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::MAXU {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ROL {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ROR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::RORI {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CLZ {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CTZ {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CPOP {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SEXT_B {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SEXT_H {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::REV8 {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ORC_B {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ZEXT_H {
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BCLR {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BEXT {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BINV {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BSET {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BCLRI {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BEXTI {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BINVI {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::BSETI {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CZERO_EQZ {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::CZERO_NEZ {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::FLW {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.float_register(),
+            },
+            Operation::FSW {
+                imm: rng.signed(12),
+                rs2: rng.float_register(),
+                rs1: rng.register(),
+            },
+            Operation::FADD_S {
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FSUB_S {
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FMUL_S {
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FDIV_S {
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FSQRT_S {
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FMADD_S {
+                rs3: rng.float_register(),
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FMSUB_S {
+                rs3: rng.float_register(),
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FNMSUB_S {
+                rs3: rng.float_register(),
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+            Operation::FNMADD_S {
+                rs3: rng.float_register(),
+                rs2: rng.float_register(),
+                rs1: rng.float_register(),
+                rd: rng.float_register(),
+                rm: rng.rounding_mode(),
+            },
+        ]
+    }
+
+    /// The RV64 only operations, and the shifts with their 6 bit shift amounts.
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    fn random_rv64_operations(rng: &mut Rng) -> Vec<Operation> {
+        vec![
+            Operation::LWU {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::LD {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SD {
+                imm: rng.signed(12),
+                rs2: rng.register(),
+                rs1: rng.register(),
+            },
+            Operation::SLLI {
+                shamt: rng.shamt(6),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRLI {
+                shamt: rng.shamt(6),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRAI {
+                shamt: rng.shamt(6),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ADDIW {
+                imm: rng.signed(12),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLLIW {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRLIW {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRAIW {
+                shamt: rng.shamt(5),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::ADDW {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SUBW {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SLLW {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRLW {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+            Operation::SRAW {
+                rs2: rng.register(),
+                rs1: rng.register(),
+                rd: rng.register(),
+            },
+        ]
+    }
+
+    #[cfg(all(feature = "ext-m", feature = "ext-a", feature = "ext-f"))]
+    #[test]
+    fn random_round_trip() {
+        let mut rng = Rng(0x1234_5678);
+        // the default configuration enables every RV32 extension
+        let rv32 = IsaConfig::default();
+        let rv64 = IsaConfig {
+            xlen: Xlen::Rv64,
+            ..IsaConfig::default()
+        };
+        for _ in 0..1000 {
+            for (operations, config) in [
+                (random_operations(&mut rng), &rv32),
+                (random_rv64_operations(&mut rng), &rv64),
+            ] {
+                for operation in operations {
+                    let word = operation.encode().unwrap();
+                    let decoded = parse_with_config(&word.to_le_bytes(), config).unwrap();
+                    assert_eq!(decoded.operation, operation, "{:#010x}", word);
+                    // reassembling the listing gives back the same encoding
+                    let line = gas_line(&decoded);
+                    let reassembled = match parse_line(&line).unwrap() {
+                        Operation::Unknown { raw } => raw,
+                        reassembled => reassembled.encode().unwrap(),
+                    };
+                    assert_eq!(reassembled, word, "{}", line);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Provides the error type returned when a binary representation can not be parsed.
+
+use core::fmt;
+
+/// Describes why a binary representation is not a valid instruction.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseError {
+    /// The opcode does not belong to any supported instruction.
+    InvalidOpcode { word: u32, opcode: u32 },
+    /// The funct3 field is not valid for the opcode.
+    InvalidFunct3 { word: u32, funct3: u32 },
+    /// The funct5 field is not valid for the opcode.
+    InvalidFunct5 { word: u32, funct5: u32 },
+    /// The funct7 field is not valid for the opcode and funct3.
+    InvalidFunct7 { word: u32, funct7: u32 },
+    /// The funct12 field does not name a known system instruction.
+    InvalidFunct12 { word: u32, funct12: u32 },
+    /// A register field could not be converted to a register.
+    InvalidRegister { word: u32 },
+    /// The instruction is valid, but belongs to an extension outside the requested
+    /// [`InstructionSet`](crate::isa::InstructionSet).
+    DisabledExtension { word: u32 },
+    /// The encoding is reserved by the specification.
+    Reserved { word: u32 },
+    /// The all-zero instruction, which is defined to be illegal in both the 16 and 32 bit
+    /// encodings so that executing zeroed memory traps.
+    Illegal,
+    /// The input ends before the end of the instruction.
+    Truncated,
+}
+
+impl ParseError {
+    /// The offending instruction bits, i.e. what a hart would write to `mtval` when raising
+    /// an illegal instruction exception (`mcause` 2) for it.
+    pub fn trap_value(&self) -> Option<u32> {
+        match self {
+            ParseError::InvalidOpcode { word, .. }
+            | ParseError::InvalidFunct3 { word, .. }
+            | ParseError::InvalidFunct5 { word, .. }
+            | ParseError::InvalidFunct7 { word, .. }
+            | ParseError::InvalidFunct12 { word, .. }
+            | ParseError::InvalidRegister { word }
+            | ParseError::DisabledExtension { word }
+            | ParseError::Reserved { word } => Some(*word),
+            ParseError::Illegal => Some(0),
+            ParseError::Truncated => None,
+        }
+    }
+
+    /// Fills in the instruction word of errors raised where it is not known, i.e. by register
+    /// conversions.
+    pub(crate) fn with_word(self, word: u32) -> Self {
+        match self {
+            ParseError::InvalidRegister { .. } => ParseError::InvalidRegister { word },
+            error => error,
+        }
+    }
+
+    /// Maps the failure of a register conversion, which is reported as a string. The word is
+    /// filled in by the caller using `with_word`.
+    pub(crate) fn invalid_register(_: &'static str) -> Self {
+        ParseError::InvalidRegister { word: 0 }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidOpcode { opcode, .. } => write!(f, "Invalid opcode! {:b}", opcode),
+            ParseError::InvalidFunct3 { funct3, .. } => write!(f, "Invalid funct3 {:b}", funct3),
+            ParseError::InvalidFunct5 { funct5, .. } => write!(f, "Invalid funct5 {:b}", funct5),
+            ParseError::InvalidFunct7 { funct7, .. } => write!(f, "Invalid funct7 {:b}", funct7),
+            ParseError::InvalidFunct12 { funct12, .. } => {
+                write!(f, "Invalid funct12 {:b}", funct12)
+            }
+            ParseError::InvalidRegister { .. } => write!(f, "Invalid register"),
+            ParseError::DisabledExtension { .. } => write!(f, "Extension is disabled"),
+            ParseError::Reserved { .. } => write!(f, "Reserved instruction"),
+            ParseError::Illegal => write!(f, "Illegal instruction"),
+            ParseError::Truncated => write!(f, "Truncated instruction"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// A [`ParseError`] together with the byte offset of the instruction in the parsed buffer,
+/// returned by [`parse_all`](crate::parse_all).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ParseErrorAt {
+    pub offset: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ParseErrorAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(word) = self.error.trap_value() {
+            write!(f, " in {:#x}", word)?;
+        }
+        write!(f, " at offset {:#x}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorAt {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn display() {
+        let error = ParseError::InvalidFunct7 {
+            word: 0x0ab50533,
+            funct7: 0b101,
+        };
+        assert_eq!(error.to_string(), "Invalid funct7 101");
+        assert_eq!(
+            ParseError::InvalidRegister { word: 0 }.to_string(),
+            "Invalid register"
+        );
+        let error = ParseErrorAt {
+            offset: 0x10,
+            error,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Invalid funct7 101 in 0xab50533 at offset 0x10"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Provides the raw fields of a 32 bit instruction word, without decoding it.
+
+/// Raw fields of a 32 bit instruction word, as laid out by the R-type format. Which of them are
+/// meaningful depends on the opcode.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InstructionFields {
+    /// Bits `[6:0]`.
+    pub opcode: u8,
+    /// Bits `[11:7]`.
+    pub rd: u8,
+    /// Bits `[14:12]`.
+    pub funct3: u8,
+    /// Bits `[19:15]`.
+    pub rs1: u8,
+    /// Bits `[24:20]`.
+    pub rs2: u8,
+    /// Bits `[31:25]`.
+    pub funct7: u8,
+}
+
+/// Splits `word` into its raw fields.
+pub fn decode_fields(word: u32) -> InstructionFields {
+    InstructionFields {
+        opcode: (word & 0b1111111) as u8,
+        rd: ((word >> 7) & 0b11111) as u8,
+        funct3: ((word >> 12) & 0b111) as u8,
+        rs1: ((word >> 15) & 0b11111) as u8,
+        rs2: ((word >> 20) & 0b11111) as u8,
+        funct7: (word >> 25) as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        // sub a0, a1, a2
+        assert_eq!(
+            decode_fields(0x40c58533),
+            InstructionFields {
+                opcode: 0b0110011,
+                rd: 10,
+                funct3: 0b000,
+                rs1: 11,
+                rs2: 12,
+                funct7: 0b0100000,
+            }
+        );
+        assert_eq!(
+            decode_fields(u32::MAX),
+            InstructionFields {
+                opcode: 0b1111111,
+                rd: 0b11111,
+                funct3: 0b111,
+                rs1: 0b11111,
+                rs2: 0b11111,
+                funct7: 0b1111111,
+            }
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Provides extraction of the sign-extended immediates of the 32 bit instruction formats.
+
+/// Sign-extends the lowest `bits` bits of `value`, with `bits` in `1..=32`.
+pub const fn sign_extend(value: u32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((value << shift) as i32) >> shift
+}
+
+/// Immediate of an I-type instruction, bits `[31:20]`.
+pub fn decode_i_imm(word: u32) -> i32 {
+    sign_extend(word >> 20, 12)
+}
+
+/// Immediate of an S-type instruction, split into bits `[31:25]` and `[11:7]`.
+pub fn decode_s_imm(word: u32) -> i32 {
+    //no idea why this is encoded this way but the ISA is what it is
+    sign_extend(
+        ((word & (0b11111 << 7)) >> 7) | ((word & (0b1111111 << 25)) >> 20),
+        12,
+    )
+}
+
+/// Branch offset of a B-type instruction, a multiple of two.
+pub fn decode_b_imm(word: u32) -> i32 {
+    sign_extend(
+        ((word & (0b1 << 31)) >> 19)
+            | ((word & (0b111111 << 25)) >> 20)
+            | ((word & (0b1111 << 8)) >> 7)
+            | ((word & (0b1 << 7)) << 4),
+        13,
+    )
+}
+
+/// Jump offset of a J-type instruction, a multiple of two.
+pub fn decode_j_imm(word: u32) -> i32 {
+    sign_extend(
+        (((word & (0b1 << 31)) >> (31 - 20))
+            | ((word & (0b1111111111 << 21)) >> (30 - 10))
+            | ((word & (0b1 << 20)) >> (20 - 11))
+            | (word & (0b11111111 << 12)))
+            & 0b1111_1111_1111_1111_1111_1111_1111_1110,
+        21,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_extension() {
+        assert_eq!(sign_extend(0x1, 1), -1);
+        assert_eq!(sign_extend(0x1, 2), 0x1);
+        assert_eq!(sign_extend(0x9, 4), -7);
+        assert_eq!(sign_extend(0x9, 5), 0x9);
+        assert_eq!(sign_extend(0xffff_ff00, 8), 0);
+        assert_eq!(sign_extend(0x8000_0000, 32), i32::MIN);
+        const MINUS_ONE: i32 = sign_extend(0xfff, 12);
+        assert_eq!(MINUS_ONE, -1);
+    }
+
+    #[test]
+    fn i_imm() {
+        // addi a0, a1, 42
+        assert_eq!(decode_i_imm(0x02a58513), 42);
+        // addi a0, a1, -1
+        assert_eq!(decode_i_imm(0xfff58513), -1);
+        // addi a0, a1, -2048
+        assert_eq!(decode_i_imm(0x80058513), -2048);
+    }
+
+    #[test]
+    fn s_imm() {
+        // sw a1, 8(a0)
+        assert_eq!(decode_s_imm(0x00b52423), 8);
+        // sw a1, -4(sp)
+        assert_eq!(decode_s_imm(0xfeb12e23), -4);
+        // sw a1, 2047(a0)
+        assert_eq!(decode_s_imm(0x7eb52fa3), 2047);
+    }
+
+    #[test]
+    fn b_imm() {
+        // beq a0, a1, 16
+        assert_eq!(decode_b_imm(0x00b50863), 16);
+        // bne a0, a1, -8
+        assert_eq!(decode_b_imm(0xfeb51ce3), -8);
+        // beq a0, a1, -4096
+        assert_eq!(decode_b_imm(0x80b50063), -4096);
+    }
+
+    #[test]
+    fn j_imm() {
+        // jal ra, 2048
+        assert_eq!(decode_j_imm(0x001000ef), 2048);
+        // jal zero, -8
+        assert_eq!(decode_j_imm(0xff9ff06f), -8);
+        // jal zero, 0xffffe
+        assert_eq!(decode_j_imm(0x7ffff06f), 0xffffe);
+    }
+}
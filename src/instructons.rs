@@ -1,18 +1,93 @@
 //! Provides a instruction type and a enum with all operations and there arguments.
 
-use crate::registers::Register;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::csr::csr_name;
+use crate::registers::{FloatRegister, Register};
+use crate::rounding::RoundingMode;
 
 /// Struct describing an instruction.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Instruction {
     pub width: InstructionWidth,
     pub operation: Operation,
+    /// The instruction bits as read, only the low halfword for compressed instructions.
+    pub raw: u32,
 }
 
 /// Enum describing the with of the corresponding binary representation of the instruction.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InstructionWidth {
     Bit32,
+    /// Compressed (RVC) instruction.
+    Bit16,
+}
+
+/// Category of an operation, see [`Operation::kind`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum InstructionKind {
+    /// Integer arithmetic, comparisons and multiplication.
+    Arithmetic,
+    /// Bitwise operations, including the bit manipulation extensions.
+    Logical,
+    /// Shifts and rotations.
+    Shift,
+    Load,
+    Store,
+    /// Conditional branch.
+    Branch,
+    /// Unconditional jump, `jal` or `jalr`.
+    Jump,
+    /// Fences, environment calls, breakpoints and trap returns.
+    System,
+    /// CSR access.
+    Csr,
+    FloatingPoint,
+    /// `lr.w`, `sc.w` and the atomic memory operations.
+    Atomic,
+    /// An encoding that was not recognized, see [`Operation::Unknown`].
+    Unknown,
+}
+
+/// Signedness of the two operands of a multiplication.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Signedness {
+    Signed,
+    Unsigned,
+    /// `rs1` is signed and `rs2` unsigned.
+    SignedUnsigned,
+}
+
+/// Determines the width of an instruction from its first halfword. Only instructions with
+/// the two lowest bits set are 32 bits wide.
+pub fn width_of(first_halfword: u16) -> InstructionWidth {
+    if first_halfword & 0b11 == 0b11 {
+        InstructionWidth::Bit32
+    } else {
+        InstructionWidth::Bit16
+    }
+}
+
+/// Length in bytes, 2 or 4, of the instruction starting with `first_halfword`. Lets a stream of
+/// mixed compressed and 32 bit instructions be walked without decoding them.
+pub fn instruction_length(first_halfword: u16) -> usize {
+    width_of(first_halfword).bytes() as usize
+}
+
+impl InstructionWidth {
+    /// Size of the binary representation in bytes.
+    pub(crate) fn bytes(&self) -> u32 {
+        match self {
+            InstructionWidth::Bit32 => 4,
+            InstructionWidth::Bit16 => 2,
+        }
+    }
 }
 
 impl Instruction {
@@ -20,96 +95,165 @@ impl Instruction {
     pub fn is_32bit(&self) -> bool {
         matches!(self.width, InstructionWidth::Bit32)
     }
+
+    /// To check if instruction width is 16 bits.
+    pub fn is_16bit(&self) -> bool {
+        matches!(self.width, InstructionWidth::Bit16)
+    }
+
+    /// Formats the instruction as assembly. Compressed instructions are spelled as their
+    /// compressed form, e.g. `c.li a0, 5` or `c.jr ra`. A 16 bit instruction whose operation has
+    /// no compressed form is formatted like a 32 bit one.
+    pub fn to_assembly(&self) -> String {
+        if self.is_32bit() {
+            return self.operation.to_string();
+        }
+        match self.operation {
+            Operation::ADDI {
+                imm: 0,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            } => String::from("c.nop"),
+            Operation::ADDI { imm, rs1, rd } if rs1 == rd => format!("c.addi {}, {}", rd, imm),
+            Operation::ADDI {
+                imm,
+                rs1: Register::ZERO,
+                rd,
+            } => format!("c.li {}, {}", rd, imm),
+            Operation::ADD {
+                rs2,
+                rs1: Register::ZERO,
+                rd,
+            } => format!("c.mv {}, {}", rd, rs2),
+            Operation::ADD { rs2, rs1, rd } if rs1 == rd => format!("c.add {}, {}", rd, rs2),
+            Operation::JAL {
+                rd: Register::ZERO,
+                imm,
+            } => format!("c.j {}", Offset(imm)),
+            Operation::JAL {
+                rd: Register::RA,
+                imm,
+            } => format!("c.jal {}", Offset(imm)),
+            Operation::JALR {
+                rd: Register::ZERO,
+                rs1,
+                imm: 0,
+            } => format!("c.jr {}", rs1),
+            Operation::JALR {
+                rd: Register::RA,
+                rs1,
+                imm: 0,
+            } => format!("c.jalr {}", rs1),
+            Operation::EBREAK {} => String::from("c.ebreak"),
+            _ => self.operation.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Formats the operation, see [`Operation`]'s `Display`. Unlike [`Instruction::to_assembly`]
+    /// compressed instructions are printed as the operation they expand to.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.operation, f)
+    }
 }
 
 /// Describes operation i.e. what type of instruction it is.
+///
+/// All immediates are sign extended. The immediates of `LUI` and `AUIPC` are already shifted
+/// into the upper 20 bits.
+///
+/// The immediates of `JAL` and the conditional branches are byte offsets relative to the
+/// address of the instruction itself. Their lowest bit is always zero, they are not counted in
+/// halfwords.
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     LUI {
         rd: Register,
-        imm: u32,
+        imm: i32,
     },
     AUIPC {
         rd: Register,
-        imm: u32,
+        imm: i32,
     },
     JAL {
         rd: Register,
-        imm: u32,
+        imm: i32,
     },
     JALR {
         rd: Register,
         rs1: Register,
-        imm: u32,
+        imm: i32,
     },
     BEQ {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     BNE {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     BLT {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     BGE {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     BLTU {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     BGEU {
-        imm: u32,
+        imm: i32,
         rs1: Register,
         rs2: Register,
     },
     LB {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     LH {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     LW {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     LBU {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     LHU {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     SB {
-        imm: u16,
+        imm: i32,
         rs2: Register,
         rs1: Register,
     },
     SH {
-        imm: u16,
+        imm: i32,
         rs2: Register,
         rs1: Register,
     },
     SW {
-        imm: u16,
+        imm: i32,
         rs2: Register,
         rs1: Register,
     },
@@ -119,27 +263,27 @@ pub enum Operation {
         rd: Register,
     },
     SLTI {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     SLTIU {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     XORI {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     ORI {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
     ANDI {
-        imm: u16,
+        imm: i32,
         rs1: Register,
         rd: Register,
     },
@@ -208,67 +352,2489 @@ pub enum Operation {
         rs1: Register,
         rd: Register,
     },
-    FENCE {
-        //fence is nop under syncrim
+    /// M extension.
+    MUL {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
     },
-    FENCE_I {
-        //fence is nop under syncrim
+    MULH {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
     },
-    ECALL {
-        //magic number
+    MULHSU {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
     },
-    EBREAK {
-        //magic number
+    MULHU {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
     },
-    MRET {
-        //magic number
+    DIV {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
     },
-    CSRRW {
-        csr: u16,
+    DIVU {
+        rs2: Register,
         rs1: Register,
         rd: Register,
     },
-    CSRRS {
-        csr: u16,
+    REM {
+        rs2: Register,
         rs1: Register,
         rd: Register,
     },
-    CSRRC {
-        csr: u16,
+    REMU {
+        rs2: Register,
         rs1: Register,
         rd: Register,
     },
-    CSRRWI {
-        csr: u16,
-        zimm: u8,
+    /// RV64 only.
+    LWU {
+        imm: i32,
+        rs1: Register,
         rd: Register,
     },
-    CSRRSI {
-        csr: u16,
-        zimm: u8,
+    /// RV64 only.
+    LD {
+        imm: i32,
+        rs1: Register,
         rd: Register,
     },
-    CSRRCI {
-        csr: u16,
-        zimm: u8,
+    /// RV64 only.
+    SD {
+        imm: i32,
+        rs2: Register,
+        rs1: Register,
+    },
+    /// RV64 only.
+    ADDIW {
+        imm: i32,
+        rs1: Register,
         rd: Register,
     },
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn instruction_size() {
-        let instruction_32 = Instruction {
-            width: InstructionWidth::Bit32,
-            operation: Operation::ADD {
-                rs2: Register::ZERO,
-                rs1: Register::ZERO,
-                rd: Register::ZERO,
-            }, //nop,
-        };
-        assert_eq!(instruction_32.is_32bit(), true);
+    /// RV64 only.
+    SLLIW {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SRLIW {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SRAIW {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    ADDW {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SUBW {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SLLW {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SRLW {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    /// RV64 only.
+    SRAW {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    CLMUL {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    CLMULH {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    CLMULR {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    SH1ADD {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    SH2ADD {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    SH3ADD {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    ANDN {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    ORN {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    XNOR {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    MIN {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    MINU {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    MAX {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    MAXU {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    ROL {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    ROR {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    RORI {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    CLZ {
+        rs1: Register,
+        rd: Register,
+    },
+    CTZ {
+        rs1: Register,
+        rd: Register,
+    },
+    CPOP {
+        rs1: Register,
+        rd: Register,
+    },
+    SEXT_B {
+        rs1: Register,
+        rd: Register,
+    },
+    SEXT_H {
+        rs1: Register,
+        rd: Register,
+    },
+    REV8 {
+        rs1: Register,
+        rd: Register,
+    },
+    ORC_B {
+        rs1: Register,
+        rd: Register,
+    },
+    ZEXT_H {
+        rs1: Register,
+        rd: Register,
+    },
+    BCLR {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    BEXT {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    BINV {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    BSET {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    BCLRI {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    BEXTI {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    BINVI {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    BSETI {
+        shamt: u8,
+        rs1: Register,
+        rd: Register,
+    },
+    CZERO_EQZ {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    CZERO_NEZ {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+    },
+    /// Memory ordering fence. `pred` and `succ` hold the `iorw` sets of the predecessor and
+    /// successor operations, with `i` as the highest bit. `fm` is the fence mode, `0b1000` for
+    /// `fence.tso`.
+    FENCE {
+        fm: u8,
+        pred: u8,
+        succ: u8,
+    },
+    FENCE_I {
+        //fence is nop under syncrim
+    },
+    /// Spin-loop hint, encoded as `fence w, 0`.
+    PAUSE {},
+    ECALL {
+        //magic number
+    },
+    EBREAK {
+        //magic number
+    },
+    MRET {
+        //magic number
+    },
+    /// Orders page table updates before later address translations. `rs1` holds the virtual
+    /// address and `rs2` the address space identifier, `zero` meaning all of them.
+    SFENCE_VMA {
+        rs1: Register,
+        rs2: Register,
+    },
+    CSRRW {
+        csr: u16,
+        rs1: Register,
+        rd: Register,
+    },
+    CSRRS {
+        csr: u16,
+        rs1: Register,
+        rd: Register,
+    },
+    CSRRC {
+        csr: u16,
+        rs1: Register,
+        rd: Register,
+    },
+    CSRRWI {
+        csr: u16,
+        zimm: u8,
+        rd: Register,
+    },
+    CSRRSI {
+        csr: u16,
+        zimm: u8,
+        rd: Register,
+    },
+    CSRRCI {
+        csr: u16,
+        zimm: u8,
+        rd: Register,
+    },
+    LR_W {
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    SC_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOSWAP_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOADD_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOXOR_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOAND_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOOR_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOMIN_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOMAX_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOMINU_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    AMOMAXU_W {
+        rs2: Register,
+        rs1: Register,
+        rd: Register,
+        aq: bool,
+        rl: bool,
+    },
+    /// F extension.
+    FLW {
+        imm: i32,
+        rs1: Register,
+        rd: FloatRegister,
+    },
+    FSW {
+        imm: i32,
+        rs2: FloatRegister,
+        rs1: Register,
+    },
+    FADD_S {
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FSUB_S {
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FMUL_S {
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FDIV_S {
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FSQRT_S {
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FMADD_S {
+        rs3: FloatRegister,
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FMSUB_S {
+        rs3: FloatRegister,
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FNMSUB_S {
+        rs3: FloatRegister,
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    FNMADD_S {
+        rs3: FloatRegister,
+        rs2: FloatRegister,
+        rs1: FloatRegister,
+        rd: FloatRegister,
+        rm: RoundingMode,
+    },
+    /// An encoding that is not recognized, holding its raw bits. Only produced by
+    /// [`parse_lenient`](crate::parse_lenient).
+    Unknown {
+        raw: u32,
+    },
+}
+
+impl Operation {
+    /// Statically predicts the address executed after this operation located at `pc`.
+    ///
+    /// Conditional branches are predicted backward-taken/forward-not-taken, `jal` and `jalr`
+    /// with a known target go to that target. Everything else, including `jalr` with an
+    /// unknown target, falls through to the next instruction as given by `width`.
+    pub fn predicted_next_pc(&self, pc: u32, width: &InstructionWidth) -> u32 {
+        let fall_through = pc.wrapping_add(width.bytes());
+        if let Some(target) = self.branch_target(pc) {
+            if target <= pc {
+                target
+            } else {
+                fall_through
+            }
+        } else {
+            self.jump_target(pc).unwrap_or(fall_through)
+        }
+    }
+
+    /// Returns the statically known addresses that can be executed after this operation
+    /// located at `pc`, with the fall-through address given by `width`.
+    ///
+    /// Conditional branches have the fall-through and the branch target, `jal` and `jalr` with
+    /// a known target only that target. A `jalr` with an unknown target and `mret` have no known
+    /// successor. Everything else, including `ecall` and `ebreak` which return to the next
+    /// instruction, falls through.
+    pub fn successors(&self, pc: u32, width: &InstructionWidth) -> Vec<u32> {
+        let fall_through = pc.wrapping_add(width.bytes());
+        if let Some(target) = self.branch_target(pc) {
+            if target == fall_through {
+                vec![fall_through]
+            } else {
+                vec![fall_through, target]
+            }
+        } else if let Some(target) = self.jump_target(pc) {
+            vec![target]
+        } else if matches!(self, Operation::JALR { .. } | Operation::MRET {}) {
+            vec![]
+        } else {
+            vec![fall_through]
+        }
+    }
+
+    /// Checks if the operation is a call, i.e. a `jal` or `jalr` linking into `ra`.
+    pub fn is_call(&self) -> bool {
+        matches!(
+            self,
+            Operation::JAL {
+                rd: Register::RA,
+                ..
+            } | Operation::JALR {
+                rd: Register::RA,
+                ..
+            }
+        )
+    }
+
+    /// Checks if the operation is a return, i.e. `jalr zero, 0(ra)`.
+    pub fn is_return(&self) -> bool {
+        matches!(
+            self,
+            Operation::JALR {
+                rd: Register::ZERO,
+                rs1: Register::RA,
+                imm: 0,
+            }
+        )
+    }
+
+    /// To check if the operation is a tail call, i.e. a `jal` or `jalr` that does not link
+    /// (`rd` is `zero`). A return (`jalr zero, 0(ra)`) is not a tail call.
+    ///
+    /// This only looks at the single instruction. A `jal zero, target` is also how ordinary
+    /// jumps inside a function are encoded, so a caller has to check that the target lies
+    /// outside the current function to tell the two apart.
+    pub fn is_tail_call(&self) -> bool {
+        match self {
+            Operation::JAL { rd, .. } => *rd == Register::ZERO,
+            Operation::JALR { rd, rs1, imm } => {
+                *rd == Register::ZERO && !(*rs1 == Register::RA && *imm == 0)
+            }
+            _ => false,
+        }
+    }
+
+    /// The signed byte offset of a conditional branch or `jal`.
+    /// Returns None for all other operations.
+    pub fn branch_offset_bytes(&self) -> Option<i32> {
+        match self {
+            Operation::JAL { imm, .. }
+            | Operation::BEQ { imm, .. }
+            | Operation::BNE { imm, .. }
+            | Operation::BLT { imm, .. }
+            | Operation::BGE { imm, .. }
+            | Operation::BLTU { imm, .. }
+            | Operation::BGEU { imm, .. } => Some(*imm),
+            _ => None,
+        }
+    }
+
+    /// Computes the target address of a conditional branch located at `pc`.
+    /// Returns None for all other operations.
+    pub fn branch_target(&self, pc: u32) -> Option<u32> {
+        match self {
+            Operation::BEQ { imm, .. }
+            | Operation::BNE { imm, .. }
+            | Operation::BLT { imm, .. }
+            | Operation::BGE { imm, .. }
+            | Operation::BLTU { imm, .. }
+            | Operation::BGEU { imm, .. } => Some(pc.wrapping_add_signed(*imm)),
+            _ => None,
+        }
+    }
+
+    /// Computes the target address of a `jal` located at `pc`. For a `jalr` the target is only
+    /// known when the base register is `zero`. Returns None for all other operations.
+    pub fn jump_target(&self, pc: u32) -> Option<u32> {
+        match self {
+            Operation::JAL { imm, .. } => Some(pc.wrapping_add_signed(*imm)),
+            Operation::JALR {
+                rs1: Register::ZERO,
+                imm,
+                ..
+            } => Some((imm & !0b1) as u32),
+            _ => None,
+        }
+    }
+
+    /// Returns the destination register, if the operation has one.
+    pub fn rd(&self) -> Option<Register> {
+        match self {
+            Operation::LUI { rd, .. }
+            | Operation::AUIPC { rd, .. }
+            | Operation::JAL { rd, .. }
+            | Operation::JALR { rd, .. }
+            | Operation::LB { rd, .. }
+            | Operation::LH { rd, .. }
+            | Operation::LW { rd, .. }
+            | Operation::LBU { rd, .. }
+            | Operation::LHU { rd, .. }
+            | Operation::ADDI { rd, .. }
+            | Operation::SLTI { rd, .. }
+            | Operation::SLTIU { rd, .. }
+            | Operation::XORI { rd, .. }
+            | Operation::ORI { rd, .. }
+            | Operation::ANDI { rd, .. }
+            | Operation::SLLI { rd, .. }
+            | Operation::SRLI { rd, .. }
+            | Operation::SRAI { rd, .. }
+            | Operation::ADD { rd, .. }
+            | Operation::SUB { rd, .. }
+            | Operation::SLL { rd, .. }
+            | Operation::SLT { rd, .. }
+            | Operation::SLTU { rd, .. }
+            | Operation::XOR { rd, .. }
+            | Operation::SRL { rd, .. }
+            | Operation::SRA { rd, .. }
+            | Operation::OR { rd, .. }
+            | Operation::AND { rd, .. }
+            | Operation::MUL { rd, .. }
+            | Operation::MULH { rd, .. }
+            | Operation::MULHSU { rd, .. }
+            | Operation::MULHU { rd, .. }
+            | Operation::DIV { rd, .. }
+            | Operation::DIVU { rd, .. }
+            | Operation::REM { rd, .. }
+            | Operation::REMU { rd, .. }
+            | Operation::LWU { rd, .. }
+            | Operation::LD { rd, .. }
+            | Operation::ADDIW { rd, .. }
+            | Operation::SLLIW { rd, .. }
+            | Operation::SRLIW { rd, .. }
+            | Operation::SRAIW { rd, .. }
+            | Operation::ADDW { rd, .. }
+            | Operation::SUBW { rd, .. }
+            | Operation::SLLW { rd, .. }
+            | Operation::SRLW { rd, .. }
+            | Operation::SRAW { rd, .. }
+            | Operation::CLMUL { rd, .. }
+            | Operation::CLMULH { rd, .. }
+            | Operation::CLMULR { rd, .. }
+            | Operation::SH1ADD { rd, .. }
+            | Operation::SH2ADD { rd, .. }
+            | Operation::SH3ADD { rd, .. }
+            | Operation::ANDN { rd, .. }
+            | Operation::ORN { rd, .. }
+            | Operation::XNOR { rd, .. }
+            | Operation::MIN { rd, .. }
+            | Operation::MINU { rd, .. }
+            | Operation::MAX { rd, .. }
+            | Operation::MAXU { rd, .. }
+            | Operation::ROL { rd, .. }
+            | Operation::ROR { rd, .. }
+            | Operation::BCLR { rd, .. }
+            | Operation::BEXT { rd, .. }
+            | Operation::BINV { rd, .. }
+            | Operation::BSET { rd, .. }
+            | Operation::CZERO_EQZ { rd, .. }
+            | Operation::CZERO_NEZ { rd, .. }
+            | Operation::RORI { rd, .. }
+            | Operation::BCLRI { rd, .. }
+            | Operation::BEXTI { rd, .. }
+            | Operation::BINVI { rd, .. }
+            | Operation::BSETI { rd, .. }
+            | Operation::CLZ { rd, .. }
+            | Operation::CTZ { rd, .. }
+            | Operation::CPOP { rd, .. }
+            | Operation::SEXT_B { rd, .. }
+            | Operation::SEXT_H { rd, .. }
+            | Operation::REV8 { rd, .. }
+            | Operation::ORC_B { rd, .. }
+            | Operation::ZEXT_H { rd, .. }
+            | Operation::CSRRW { rd, .. }
+            | Operation::CSRRS { rd, .. }
+            | Operation::CSRRC { rd, .. }
+            | Operation::CSRRWI { rd, .. }
+            | Operation::CSRRSI { rd, .. }
+            | Operation::CSRRCI { rd, .. }
+            | Operation::LR_W { rd, .. }
+            | Operation::SC_W { rd, .. }
+            | Operation::AMOSWAP_W { rd, .. }
+            | Operation::AMOADD_W { rd, .. }
+            | Operation::AMOXOR_W { rd, .. }
+            | Operation::AMOAND_W { rd, .. }
+            | Operation::AMOOR_W { rd, .. }
+            | Operation::AMOMIN_W { rd, .. }
+            | Operation::AMOMAX_W { rd, .. }
+            | Operation::AMOMINU_W { rd, .. }
+            | Operation::AMOMAXU_W { rd, .. } => Some(*rd),
+            _ => None,
+        }
+    }
+
+    /// Returns the first source register, if the operation has one. For loads, stores and
+    /// atomics this is the base address register.
+    pub fn rs1(&self) -> Option<Register> {
+        match self {
+            Operation::JALR { rs1, .. }
+            | Operation::LB { rs1, .. }
+            | Operation::LH { rs1, .. }
+            | Operation::LW { rs1, .. }
+            | Operation::LBU { rs1, .. }
+            | Operation::LHU { rs1, .. }
+            | Operation::LWU { rs1, .. }
+            | Operation::LD { rs1, .. }
+            | Operation::ADDI { rs1, .. }
+            | Operation::SLTI { rs1, .. }
+            | Operation::SLTIU { rs1, .. }
+            | Operation::XORI { rs1, .. }
+            | Operation::ORI { rs1, .. }
+            | Operation::ANDI { rs1, .. }
+            | Operation::SLLI { rs1, .. }
+            | Operation::SRLI { rs1, .. }
+            | Operation::SRAI { rs1, .. }
+            | Operation::CSRRW { rs1, .. }
+            | Operation::SFENCE_VMA { rs1, .. }
+            | Operation::CSRRS { rs1, .. }
+            | Operation::CSRRC { rs1, .. }
+            | Operation::LR_W { rs1, .. }
+            | Operation::BEQ { rs1, .. }
+            | Operation::BNE { rs1, .. }
+            | Operation::BLT { rs1, .. }
+            | Operation::BGE { rs1, .. }
+            | Operation::BLTU { rs1, .. }
+            | Operation::BGEU { rs1, .. }
+            | Operation::SB { rs1, .. }
+            | Operation::SH { rs1, .. }
+            | Operation::SW { rs1, .. }
+            | Operation::SD { rs1, .. }
+            | Operation::FLW { rs1, .. }
+            | Operation::FSW { rs1, .. }
+            | Operation::ADD { rs1, .. }
+            | Operation::SUB { rs1, .. }
+            | Operation::SLL { rs1, .. }
+            | Operation::SLT { rs1, .. }
+            | Operation::SLTU { rs1, .. }
+            | Operation::XOR { rs1, .. }
+            | Operation::SRL { rs1, .. }
+            | Operation::SRA { rs1, .. }
+            | Operation::OR { rs1, .. }
+            | Operation::AND { rs1, .. }
+            | Operation::MUL { rs1, .. }
+            | Operation::MULH { rs1, .. }
+            | Operation::MULHSU { rs1, .. }
+            | Operation::MULHU { rs1, .. }
+            | Operation::DIV { rs1, .. }
+            | Operation::DIVU { rs1, .. }
+            | Operation::REM { rs1, .. }
+            | Operation::REMU { rs1, .. }
+            | Operation::ADDIW { rs1, .. }
+            | Operation::SLLIW { rs1, .. }
+            | Operation::SRLIW { rs1, .. }
+            | Operation::SRAIW { rs1, .. }
+            | Operation::ADDW { rs1, .. }
+            | Operation::SUBW { rs1, .. }
+            | Operation::SLLW { rs1, .. }
+            | Operation::SRLW { rs1, .. }
+            | Operation::SRAW { rs1, .. }
+            | Operation::CLMUL { rs1, .. }
+            | Operation::CLMULH { rs1, .. }
+            | Operation::CLMULR { rs1, .. }
+            | Operation::SH1ADD { rs1, .. }
+            | Operation::SH2ADD { rs1, .. }
+            | Operation::SH3ADD { rs1, .. }
+            | Operation::ANDN { rs1, .. }
+            | Operation::ORN { rs1, .. }
+            | Operation::XNOR { rs1, .. }
+            | Operation::MIN { rs1, .. }
+            | Operation::MINU { rs1, .. }
+            | Operation::MAX { rs1, .. }
+            | Operation::MAXU { rs1, .. }
+            | Operation::ROL { rs1, .. }
+            | Operation::ROR { rs1, .. }
+            | Operation::BCLR { rs1, .. }
+            | Operation::BEXT { rs1, .. }
+            | Operation::BINV { rs1, .. }
+            | Operation::BSET { rs1, .. }
+            | Operation::CZERO_EQZ { rs1, .. }
+            | Operation::CZERO_NEZ { rs1, .. }
+            | Operation::RORI { rs1, .. }
+            | Operation::BCLRI { rs1, .. }
+            | Operation::BEXTI { rs1, .. }
+            | Operation::BINVI { rs1, .. }
+            | Operation::BSETI { rs1, .. }
+            | Operation::CLZ { rs1, .. }
+            | Operation::CTZ { rs1, .. }
+            | Operation::CPOP { rs1, .. }
+            | Operation::SEXT_B { rs1, .. }
+            | Operation::SEXT_H { rs1, .. }
+            | Operation::REV8 { rs1, .. }
+            | Operation::ORC_B { rs1, .. }
+            | Operation::ZEXT_H { rs1, .. }
+            | Operation::SC_W { rs1, .. }
+            | Operation::AMOSWAP_W { rs1, .. }
+            | Operation::AMOADD_W { rs1, .. }
+            | Operation::AMOXOR_W { rs1, .. }
+            | Operation::AMOAND_W { rs1, .. }
+            | Operation::AMOOR_W { rs1, .. }
+            | Operation::AMOMIN_W { rs1, .. }
+            | Operation::AMOMAX_W { rs1, .. }
+            | Operation::AMOMINU_W { rs1, .. }
+            | Operation::AMOMAXU_W { rs1, .. } => Some(*rs1),
+            _ => None,
+        }
+    }
+
+    /// Returns the second source register, if the operation has one. For stores this is the
+    /// register holding the value to store.
+    pub fn rs2(&self) -> Option<Register> {
+        match self {
+            Operation::BEQ { rs2, .. }
+            | Operation::BNE { rs2, .. }
+            | Operation::BLT { rs2, .. }
+            | Operation::BGE { rs2, .. }
+            | Operation::BLTU { rs2, .. }
+            | Operation::BGEU { rs2, .. }
+            | Operation::SFENCE_VMA { rs2, .. }
+            | Operation::SB { rs2, .. }
+            | Operation::SH { rs2, .. }
+            | Operation::SW { rs2, .. }
+            | Operation::SD { rs2, .. }
+            | Operation::ADD { rs2, .. }
+            | Operation::SUB { rs2, .. }
+            | Operation::SLL { rs2, .. }
+            | Operation::SLT { rs2, .. }
+            | Operation::SLTU { rs2, .. }
+            | Operation::XOR { rs2, .. }
+            | Operation::SRL { rs2, .. }
+            | Operation::SRA { rs2, .. }
+            | Operation::OR { rs2, .. }
+            | Operation::AND { rs2, .. }
+            | Operation::MUL { rs2, .. }
+            | Operation::MULH { rs2, .. }
+            | Operation::MULHSU { rs2, .. }
+            | Operation::MULHU { rs2, .. }
+            | Operation::DIV { rs2, .. }
+            | Operation::DIVU { rs2, .. }
+            | Operation::REM { rs2, .. }
+            | Operation::REMU { rs2, .. }
+            | Operation::ADDW { rs2, .. }
+            | Operation::SUBW { rs2, .. }
+            | Operation::SLLW { rs2, .. }
+            | Operation::SRLW { rs2, .. }
+            | Operation::SRAW { rs2, .. }
+            | Operation::CLMUL { rs2, .. }
+            | Operation::CLMULH { rs2, .. }
+            | Operation::CLMULR { rs2, .. }
+            | Operation::SH1ADD { rs2, .. }
+            | Operation::SH2ADD { rs2, .. }
+            | Operation::SH3ADD { rs2, .. }
+            | Operation::ANDN { rs2, .. }
+            | Operation::ORN { rs2, .. }
+            | Operation::XNOR { rs2, .. }
+            | Operation::MIN { rs2, .. }
+            | Operation::MINU { rs2, .. }
+            | Operation::MAX { rs2, .. }
+            | Operation::MAXU { rs2, .. }
+            | Operation::ROL { rs2, .. }
+            | Operation::ROR { rs2, .. }
+            | Operation::BCLR { rs2, .. }
+            | Operation::BEXT { rs2, .. }
+            | Operation::BINV { rs2, .. }
+            | Operation::BSET { rs2, .. }
+            | Operation::CZERO_EQZ { rs2, .. }
+            | Operation::CZERO_NEZ { rs2, .. }
+            | Operation::SC_W { rs2, .. }
+            | Operation::AMOSWAP_W { rs2, .. }
+            | Operation::AMOADD_W { rs2, .. }
+            | Operation::AMOXOR_W { rs2, .. }
+            | Operation::AMOAND_W { rs2, .. }
+            | Operation::AMOOR_W { rs2, .. }
+            | Operation::AMOMIN_W { rs2, .. }
+            | Operation::AMOMAX_W { rs2, .. }
+            | Operation::AMOMINU_W { rs2, .. }
+            | Operation::AMOMAXU_W { rs2, .. } => Some(*rs2),
+            _ => None,
+        }
+    }
+
+    /// Checks if the operation is the canonical `nop`, i.e. `addi zero, zero, 0`.
+    pub fn is_nop(&self) -> bool {
+        matches!(
+            self,
+            Operation::ADDI {
+                imm: 0,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            }
+        )
+    }
+
+    /// Checks if the operation is a conditional branch.
+    pub fn is_branch(&self) -> bool {
+        matches!(
+            self,
+            Operation::BEQ { .. }
+                | Operation::BNE { .. }
+                | Operation::BLT { .. }
+                | Operation::BGE { .. }
+                | Operation::BLTU { .. }
+                | Operation::BGEU { .. }
+        )
+    }
+
+    /// Checks if the operation is an unconditional jump, i.e. `jal` or `jalr`.
+    pub fn is_jump(&self) -> bool {
+        matches!(self, Operation::JAL { .. } | Operation::JALR { .. })
+    }
+
+    /// Checks if the operation can transfer control anywhere but the next instruction, i.e. a
+    /// conditional branch, a jump, an environment call or breakpoint, or a trap return.
+    pub fn is_control_flow(&self) -> bool {
+        self.is_branch()
+            || self.is_jump()
+            || matches!(
+                self,
+                Operation::ECALL {} | Operation::EBREAK {} | Operation::MRET {}
+            )
+    }
+
+    /// Checks if the operation reads memory. This includes `lr.w` and the atomic memory
+    /// operations, which both read and write memory.
+    pub fn is_load(&self) -> bool {
+        matches!(
+            self,
+            Operation::LB { .. }
+                | Operation::LH { .. }
+                | Operation::LW { .. }
+                | Operation::LBU { .. }
+                | Operation::LHU { .. }
+                | Operation::LWU { .. }
+                | Operation::LD { .. }
+                | Operation::FLW { .. }
+                | Operation::LR_W { .. }
+                | Operation::AMOSWAP_W { .. }
+                | Operation::AMOADD_W { .. }
+                | Operation::AMOXOR_W { .. }
+                | Operation::AMOAND_W { .. }
+                | Operation::AMOOR_W { .. }
+                | Operation::AMOMIN_W { .. }
+                | Operation::AMOMAX_W { .. }
+                | Operation::AMOMINU_W { .. }
+                | Operation::AMOMAXU_W { .. }
+        )
+    }
+
+    /// Checks if the operation writes memory. This includes `sc.w` and the atomic memory
+    /// operations, which both read and write memory.
+    pub fn is_store(&self) -> bool {
+        matches!(
+            self,
+            Operation::SB { .. }
+                | Operation::SH { .. }
+                | Operation::SW { .. }
+                | Operation::SD { .. }
+                | Operation::FSW { .. }
+                | Operation::SC_W { .. }
+                | Operation::AMOSWAP_W { .. }
+                | Operation::AMOADD_W { .. }
+                | Operation::AMOXOR_W { .. }
+                | Operation::AMOAND_W { .. }
+                | Operation::AMOOR_W { .. }
+                | Operation::AMOMIN_W { .. }
+                | Operation::AMOMAX_W { .. }
+                | Operation::AMOMINU_W { .. }
+                | Operation::AMOMAXU_W { .. }
+        )
+    }
+
+    /// Checks if the operation reads memory. The same as [`Operation::is_load`], named as the
+    /// counterpart of [`Operation::writes_memory`] for memory effect analysis.
+    pub fn reads_memory(&self) -> bool {
+        self.is_load()
+    }
+
+    /// Checks if the operation writes memory. The same as [`Operation::is_store`].
+    pub fn writes_memory(&self) -> bool {
+        self.is_store()
+    }
+
+    /// Checks if the operation is in the SYSTEM opcode, i.e. an environment call, trap return or
+    /// CSR access.
+    pub fn is_system(&self) -> bool {
+        matches!(
+            self,
+            Operation::ECALL { .. }
+                | Operation::EBREAK { .. }
+                | Operation::MRET { .. }
+                | Operation::SFENCE_VMA { .. }
+                | Operation::CSRRW { .. }
+                | Operation::CSRRS { .. }
+                | Operation::CSRRC { .. }
+                | Operation::CSRRWI { .. }
+                | Operation::CSRRSI { .. }
+                | Operation::CSRRCI { .. }
+        )
+    }
+
+    /// Checks if the operation only computes a value from registers and immediates into `rd`.
+    pub fn is_alu(&self) -> bool {
+        matches!(
+            self,
+            Operation::LUI { .. }
+                | Operation::AUIPC { .. }
+                | Operation::ADDI { .. }
+                | Operation::SLTI { .. }
+                | Operation::SLTIU { .. }
+                | Operation::XORI { .. }
+                | Operation::ORI { .. }
+                | Operation::ANDI { .. }
+                | Operation::SLLI { .. }
+                | Operation::SRLI { .. }
+                | Operation::SRAI { .. }
+                | Operation::ADD { .. }
+                | Operation::SUB { .. }
+                | Operation::SLL { .. }
+                | Operation::SLT { .. }
+                | Operation::SLTU { .. }
+                | Operation::XOR { .. }
+                | Operation::SRL { .. }
+                | Operation::SRA { .. }
+                | Operation::OR { .. }
+                | Operation::AND { .. }
+                | Operation::MUL { .. }
+                | Operation::MULH { .. }
+                | Operation::MULHSU { .. }
+                | Operation::MULHU { .. }
+                | Operation::DIV { .. }
+                | Operation::DIVU { .. }
+                | Operation::REM { .. }
+                | Operation::REMU { .. }
+                | Operation::ADDIW { .. }
+                | Operation::SLLIW { .. }
+                | Operation::SRLIW { .. }
+                | Operation::SRAIW { .. }
+                | Operation::ADDW { .. }
+                | Operation::SUBW { .. }
+                | Operation::SLLW { .. }
+                | Operation::SRLW { .. }
+                | Operation::SRAW { .. }
+                | Operation::CLMUL { .. }
+                | Operation::CLMULH { .. }
+                | Operation::CLMULR { .. }
+                | Operation::SH1ADD { .. }
+                | Operation::SH2ADD { .. }
+                | Operation::SH3ADD { .. }
+                | Operation::ANDN { .. }
+                | Operation::ORN { .. }
+                | Operation::XNOR { .. }
+                | Operation::MIN { .. }
+                | Operation::MINU { .. }
+                | Operation::MAX { .. }
+                | Operation::MAXU { .. }
+                | Operation::ROL { .. }
+                | Operation::ROR { .. }
+                | Operation::BCLR { .. }
+                | Operation::BEXT { .. }
+                | Operation::BINV { .. }
+                | Operation::BSET { .. }
+                | Operation::CZERO_EQZ { .. }
+                | Operation::CZERO_NEZ { .. }
+                | Operation::RORI { .. }
+                | Operation::BCLRI { .. }
+                | Operation::BEXTI { .. }
+                | Operation::BINVI { .. }
+                | Operation::BSETI { .. }
+                | Operation::CLZ { .. }
+                | Operation::CTZ { .. }
+                | Operation::CPOP { .. }
+                | Operation::SEXT_B { .. }
+                | Operation::SEXT_H { .. }
+                | Operation::REV8 { .. }
+                | Operation::ORC_B { .. }
+                | Operation::ZEXT_H { .. }
+        )
+    }
+
+    /// Checks which half of the product a multiplication returns: `Some(false)` for the low
+    /// bits (`mul`), `Some(true)` for the high bits (`mulh`, `mulhsu`, `mulhu`) and None for
+    /// other operations.
+    pub fn product_is_high(&self) -> Option<bool> {
+        match self {
+            Operation::MUL { .. } => Some(false),
+            Operation::MULH { .. } | Operation::MULHSU { .. } | Operation::MULHU { .. } => {
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the signedness of the operands of a high-bits multiplication. None for all other
+    /// operations, including `mul` whose result does not depend on it.
+    pub fn product_signedness(&self) -> Option<Signedness> {
+        match self {
+            Operation::MULH { .. } => Some(Signedness::Signed),
+            Operation::MULHSU { .. } => Some(Signedness::SignedUnsigned),
+            Operation::MULHU { .. } => Some(Signedness::Unsigned),
+            _ => None,
+        }
+    }
+
+    /// Checks if the operation is a division or remainder, which have defined results for
+    /// division by zero and for the signed overflow of dividing the most negative value by -1.
+    pub fn is_division(&self) -> bool {
+        matches!(
+            self,
+            Operation::DIV { .. }
+                | Operation::DIVU { .. }
+                | Operation::REM { .. }
+                | Operation::REMU { .. }
+        )
+    }
+
+    /// Returns the category of the operation, for grouping and statistics.
+    pub fn kind(&self) -> InstructionKind {
+        match self {
+            Operation::LUI { .. }
+            | Operation::AUIPC { .. }
+            | Operation::ADDI { .. }
+            | Operation::SLTI { .. }
+            | Operation::SLTIU { .. }
+            | Operation::ADD { .. }
+            | Operation::SUB { .. }
+            | Operation::SLT { .. }
+            | Operation::SLTU { .. }
+            | Operation::MUL { .. }
+            | Operation::MULH { .. }
+            | Operation::MULHSU { .. }
+            | Operation::MULHU { .. }
+            | Operation::DIV { .. }
+            | Operation::DIVU { .. }
+            | Operation::REM { .. }
+            | Operation::REMU { .. }
+            | Operation::ADDIW { .. }
+            | Operation::ADDW { .. }
+            | Operation::SUBW { .. }
+            | Operation::CLMUL { .. }
+            | Operation::CLMULH { .. }
+            | Operation::CLMULR { .. }
+            | Operation::SH1ADD { .. }
+            | Operation::SH2ADD { .. }
+            | Operation::SH3ADD { .. }
+            | Operation::MIN { .. }
+            | Operation::MINU { .. }
+            | Operation::MAX { .. }
+            | Operation::MAXU { .. } => InstructionKind::Arithmetic,
+            Operation::XORI { .. }
+            | Operation::ORI { .. }
+            | Operation::ANDI { .. }
+            | Operation::XOR { .. }
+            | Operation::OR { .. }
+            | Operation::AND { .. }
+            | Operation::ANDN { .. }
+            | Operation::ORN { .. }
+            | Operation::XNOR { .. }
+            | Operation::CLZ { .. }
+            | Operation::CTZ { .. }
+            | Operation::CPOP { .. }
+            | Operation::SEXT_B { .. }
+            | Operation::SEXT_H { .. }
+            | Operation::REV8 { .. }
+            | Operation::ORC_B { .. }
+            | Operation::ZEXT_H { .. }
+            | Operation::BCLR { .. }
+            | Operation::BEXT { .. }
+            | Operation::BINV { .. }
+            | Operation::BSET { .. }
+            | Operation::BCLRI { .. }
+            | Operation::BEXTI { .. }
+            | Operation::BINVI { .. }
+            | Operation::BSETI { .. }
+            | Operation::CZERO_EQZ { .. }
+            | Operation::CZERO_NEZ { .. } => InstructionKind::Logical,
+            Operation::SLLI { .. }
+            | Operation::SRLI { .. }
+            | Operation::SRAI { .. }
+            | Operation::SLL { .. }
+            | Operation::SRL { .. }
+            | Operation::SRA { .. }
+            | Operation::SLLIW { .. }
+            | Operation::SRLIW { .. }
+            | Operation::SRAIW { .. }
+            | Operation::SLLW { .. }
+            | Operation::SRLW { .. }
+            | Operation::SRAW { .. }
+            | Operation::ROL { .. }
+            | Operation::ROR { .. }
+            | Operation::RORI { .. } => InstructionKind::Shift,
+            Operation::LB { .. }
+            | Operation::LH { .. }
+            | Operation::LW { .. }
+            | Operation::LBU { .. }
+            | Operation::LHU { .. }
+            | Operation::LWU { .. }
+            | Operation::LD { .. }
+            | Operation::FLW { .. } => InstructionKind::Load,
+            Operation::SB { .. }
+            | Operation::SH { .. }
+            | Operation::SW { .. }
+            | Operation::SD { .. }
+            | Operation::FSW { .. } => InstructionKind::Store,
+            Operation::BEQ { .. }
+            | Operation::BNE { .. }
+            | Operation::BLT { .. }
+            | Operation::BGE { .. }
+            | Operation::BLTU { .. }
+            | Operation::BGEU { .. } => InstructionKind::Branch,
+            Operation::JAL { .. } | Operation::JALR { .. } => InstructionKind::Jump,
+            Operation::FENCE { .. }
+            | Operation::FENCE_I { .. }
+            | Operation::PAUSE { .. }
+            | Operation::ECALL { .. }
+            | Operation::EBREAK { .. }
+            | Operation::MRET { .. }
+            | Operation::SFENCE_VMA { .. } => InstructionKind::System,
+            Operation::CSRRW { .. }
+            | Operation::CSRRS { .. }
+            | Operation::CSRRC { .. }
+            | Operation::CSRRWI { .. }
+            | Operation::CSRRSI { .. }
+            | Operation::CSRRCI { .. } => InstructionKind::Csr,
+            Operation::FADD_S { .. }
+            | Operation::FSUB_S { .. }
+            | Operation::FMUL_S { .. }
+            | Operation::FDIV_S { .. }
+            | Operation::FSQRT_S { .. }
+            | Operation::FMADD_S { .. }
+            | Operation::FMSUB_S { .. }
+            | Operation::FNMSUB_S { .. }
+            | Operation::FNMADD_S { .. } => InstructionKind::FloatingPoint,
+            Operation::LR_W { .. }
+            | Operation::SC_W { .. }
+            | Operation::AMOSWAP_W { .. }
+            | Operation::AMOADD_W { .. }
+            | Operation::AMOXOR_W { .. }
+            | Operation::AMOAND_W { .. }
+            | Operation::AMOOR_W { .. }
+            | Operation::AMOMIN_W { .. }
+            | Operation::AMOMAX_W { .. }
+            | Operation::AMOMINU_W { .. }
+            | Operation::AMOMAXU_W { .. } => InstructionKind::Atomic,
+            Operation::Unknown { .. } => InstructionKind::Unknown,
+        }
+    }
+
+    /// Returns the mnemonic of the operation without any operands, e.g. `addi` or `lr.w`.
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Operation::LUI { .. } => "lui",
+            Operation::AUIPC { .. } => "auipc",
+            Operation::JAL { .. } => "jal",
+            Operation::JALR { .. } => "jalr",
+            Operation::BEQ { .. } => "beq",
+            Operation::BNE { .. } => "bne",
+            Operation::BLT { .. } => "blt",
+            Operation::BGE { .. } => "bge",
+            Operation::BLTU { .. } => "bltu",
+            Operation::BGEU { .. } => "bgeu",
+            Operation::LB { .. } => "lb",
+            Operation::LH { .. } => "lh",
+            Operation::LW { .. } => "lw",
+            Operation::LBU { .. } => "lbu",
+            Operation::LHU { .. } => "lhu",
+            Operation::SB { .. } => "sb",
+            Operation::SH { .. } => "sh",
+            Operation::SW { .. } => "sw",
+            Operation::ADDI { .. } => "addi",
+            Operation::SLTI { .. } => "slti",
+            Operation::SLTIU { .. } => "sltiu",
+            Operation::XORI { .. } => "xori",
+            Operation::ORI { .. } => "ori",
+            Operation::ANDI { .. } => "andi",
+            Operation::SLLI { .. } => "slli",
+            Operation::SRLI { .. } => "srli",
+            Operation::SRAI { .. } => "srai",
+            Operation::ADD { .. } => "add",
+            Operation::SUB { .. } => "sub",
+            Operation::SLL { .. } => "sll",
+            Operation::SLT { .. } => "slt",
+            Operation::SLTU { .. } => "sltu",
+            Operation::XOR { .. } => "xor",
+            Operation::SRL { .. } => "srl",
+            Operation::SRA { .. } => "sra",
+            Operation::OR { .. } => "or",
+            Operation::AND { .. } => "and",
+            Operation::MUL { .. } => "mul",
+            Operation::MULH { .. } => "mulh",
+            Operation::MULHSU { .. } => "mulhsu",
+            Operation::MULHU { .. } => "mulhu",
+            Operation::DIV { .. } => "div",
+            Operation::DIVU { .. } => "divu",
+            Operation::REM { .. } => "rem",
+            Operation::REMU { .. } => "remu",
+            Operation::LWU { .. } => "lwu",
+            Operation::LD { .. } => "ld",
+            Operation::SD { .. } => "sd",
+            Operation::ADDIW { .. } => "addiw",
+            Operation::SLLIW { .. } => "slliw",
+            Operation::SRLIW { .. } => "srliw",
+            Operation::SRAIW { .. } => "sraiw",
+            Operation::ADDW { .. } => "addw",
+            Operation::SUBW { .. } => "subw",
+            Operation::SLLW { .. } => "sllw",
+            Operation::SRLW { .. } => "srlw",
+            Operation::SRAW { .. } => "sraw",
+            Operation::CLMUL { .. } => "clmul",
+            Operation::CLMULH { .. } => "clmulh",
+            Operation::CLMULR { .. } => "clmulr",
+            Operation::SH1ADD { .. } => "sh1add",
+            Operation::SH2ADD { .. } => "sh2add",
+            Operation::SH3ADD { .. } => "sh3add",
+            Operation::ANDN { .. } => "andn",
+            Operation::ORN { .. } => "orn",
+            Operation::XNOR { .. } => "xnor",
+            Operation::MIN { .. } => "min",
+            Operation::MINU { .. } => "minu",
+            Operation::MAX { .. } => "max",
+            Operation::MAXU { .. } => "maxu",
+            Operation::ROL { .. } => "rol",
+            Operation::ROR { .. } => "ror",
+            Operation::BCLR { .. } => "bclr",
+            Operation::BEXT { .. } => "bext",
+            Operation::BINV { .. } => "binv",
+            Operation::BSET { .. } => "bset",
+            Operation::CZERO_EQZ { .. } => "czero.eqz",
+            Operation::CZERO_NEZ { .. } => "czero.nez",
+            Operation::RORI { .. } => "rori",
+            Operation::BCLRI { .. } => "bclri",
+            Operation::BEXTI { .. } => "bexti",
+            Operation::BINVI { .. } => "binvi",
+            Operation::BSETI { .. } => "bseti",
+            Operation::CLZ { .. } => "clz",
+            Operation::CTZ { .. } => "ctz",
+            Operation::CPOP { .. } => "cpop",
+            Operation::SEXT_B { .. } => "sext.b",
+            Operation::SEXT_H { .. } => "sext.h",
+            Operation::REV8 { .. } => "rev8",
+            Operation::ORC_B { .. } => "orc.b",
+            Operation::ZEXT_H { .. } => "zext.h",
+            Operation::FENCE {
+                fm: 0b1000,
+                pred: 0b0011,
+                succ: 0b0011,
+            } => "fence.tso",
+            Operation::FENCE { .. } => "fence",
+            Operation::FENCE_I { .. } => "fence.i",
+            Operation::PAUSE { .. } => "pause",
+            Operation::ECALL { .. } => "ecall",
+            Operation::EBREAK { .. } => "ebreak",
+            Operation::MRET { .. } => "mret",
+            Operation::SFENCE_VMA { .. } => "sfence.vma",
+            Operation::CSRRW { .. } => "csrrw",
+            Operation::CSRRS { .. } => "csrrs",
+            Operation::CSRRC { .. } => "csrrc",
+            Operation::CSRRWI { .. } => "csrrwi",
+            Operation::CSRRSI { .. } => "csrrsi",
+            Operation::CSRRCI { .. } => "csrrci",
+            Operation::LR_W { .. } => "lr.w",
+            Operation::SC_W { .. } => "sc.w",
+            Operation::AMOSWAP_W { .. } => "amoswap.w",
+            Operation::AMOADD_W { .. } => "amoadd.w",
+            Operation::AMOXOR_W { .. } => "amoxor.w",
+            Operation::AMOAND_W { .. } => "amoand.w",
+            Operation::AMOOR_W { .. } => "amoor.w",
+            Operation::AMOMIN_W { .. } => "amomin.w",
+            Operation::AMOMAX_W { .. } => "amomax.w",
+            Operation::AMOMINU_W { .. } => "amominu.w",
+            Operation::AMOMAXU_W { .. } => "amomaxu.w",
+            Operation::FLW { .. } => "flw",
+            Operation::FSW { .. } => "fsw",
+            Operation::FADD_S { .. } => "fadd.s",
+            Operation::FSUB_S { .. } => "fsub.s",
+            Operation::FMUL_S { .. } => "fmul.s",
+            Operation::FDIV_S { .. } => "fdiv.s",
+            Operation::FSQRT_S { .. } => "fsqrt.s",
+            Operation::FMADD_S { .. } => "fmadd.s",
+            Operation::FMSUB_S { .. } => "fmsub.s",
+            Operation::FNMSUB_S { .. } => "fnmsub.s",
+            Operation::FNMADD_S { .. } => "fnmadd.s",
+            Operation::Unknown { .. } => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    /// Formats the operation as assembly, e.g. `add a1, a0, t0` or `lw a0, 8(sp)`.
+    /// Branch and jump offsets are printed relative to the instruction. With the alternate flag
+    /// (`{:#}`) operations that have a pseudo-instruction alias are printed as it, e.g. `mv a0, a1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            if let Some(pseudo) = self.as_pseudo() {
+                return write!(f, "{}", pseudo);
+            }
+        }
+        let mnemonic = self.mnemonic();
+        match self {
+            Operation::LUI { rd, imm } => {
+                write!(f, "{} {}, {:#x}", mnemonic, rd, *imm as u32 >> 12)
+            }
+            Operation::AUIPC { rd, imm } => {
+                write!(f, "{} {}, {:#x}", mnemonic, rd, *imm as u32 >> 12)
+            }
+            Operation::JAL { rd, imm } => {
+                write!(f, "{} {}, {}", mnemonic, rd, Offset(*imm))
+            }
+            Operation::JALR { rd, rs1, imm } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::BEQ { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::BNE { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::BLT { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::BGE { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::BLTU { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::BGEU { imm, rs1, rs2 } => write_branch(f, mnemonic, rs1, rs2, *imm),
+            Operation::LB { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::LH { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::LW { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::LBU { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::LHU { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::SB { imm, rs2, rs1 } => write_memory(f, mnemonic, rs2, *imm, rs1),
+            Operation::SH { imm, rs2, rs1 } => write_memory(f, mnemonic, rs2, *imm, rs1),
+            Operation::SW { imm, rs2, rs1 } => write_memory(f, mnemonic, rs2, *imm, rs1),
+            Operation::ADDI { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::SLTI { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::SLTIU { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::XORI { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::ORI { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::ANDI { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::SLLI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::SRLI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::SRAI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::ADD { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SUB { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SLL { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SLT { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SLTU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::XOR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SRL { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SRA { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::OR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::AND { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MUL { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MULH { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MULHSU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MULHU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::DIV { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::DIVU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::REM { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::REMU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::LWU { imm, rs1, rd } | Operation::LD { imm, rs1, rd } => {
+                write_memory(f, mnemonic, rd, *imm, rs1)
+            }
+            Operation::SD { imm, rs2, rs1 } => write_memory(f, mnemonic, rs2, *imm, rs1),
+            Operation::ADDIW { imm, rs1, rd } => write_immediate(f, mnemonic, rd, rs1, *imm),
+            Operation::SLLIW { shamt, rs1, rd }
+            | Operation::SRLIW { shamt, rs1, rd }
+            | Operation::SRAIW { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::ADDW { rs2, rs1, rd }
+            | Operation::SUBW { rs2, rs1, rd }
+            | Operation::SLLW { rs2, rs1, rd }
+            | Operation::SRLW { rs2, rs1, rd }
+            | Operation::SRAW { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::CLMUL { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::CLMULH { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::CLMULR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SH1ADD { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SH2ADD { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::SH3ADD { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::ANDN { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::ORN { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::XNOR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MIN { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MINU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MAX { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::MAXU { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::ROL { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::ROR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::BCLR { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::BEXT { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::BINV { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::BSET { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::CZERO_EQZ { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::CZERO_NEZ { rs2, rs1, rd } => write_register(f, mnemonic, rd, rs1, rs2),
+            Operation::RORI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::BCLRI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::BEXTI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::BINVI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::BSETI { shamt, rs1, rd } => {
+                write_immediate(f, mnemonic, rd, rs1, *shamt as i32)
+            }
+            Operation::CLZ { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::CTZ { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::CPOP { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::SEXT_B { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::SEXT_H { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::REV8 { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::ORC_B { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::ZEXT_H { rs1, rd } => write_unary(f, mnemonic, rd, rs1),
+            Operation::FENCE { pred, succ, .. } if mnemonic == "fence" => {
+                write!(f, "{} {}, {}", mnemonic, FenceSet(*pred), FenceSet(*succ))
+            }
+            Operation::FENCE { .. }
+            | Operation::FENCE_I {}
+            | Operation::PAUSE {}
+            | Operation::ECALL {}
+            | Operation::EBREAK {}
+            | Operation::MRET {} => f.write_str(mnemonic),
+            Operation::SFENCE_VMA { rs1, rs2 } => write!(f, "{} {}, {}", mnemonic, rs1, rs2),
+            Operation::CSRRW { csr, rs1, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), rs1)
+            }
+            Operation::CSRRS { csr, rs1, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), rs1)
+            }
+            Operation::CSRRC { csr, rs1, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), rs1)
+            }
+            Operation::CSRRWI { csr, zimm, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), zimm)
+            }
+            Operation::CSRRSI { csr, zimm, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), zimm)
+            }
+            Operation::CSRRCI { csr, zimm, rd } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, Csr(*csr), zimm)
+            }
+            Operation::LR_W { rs1, rd, aq, rl } => {
+                write!(f, "{}{} {}, ({})", mnemonic, ordering(*aq, *rl), rd, rs1)
+            }
+            Operation::SC_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOSWAP_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOADD_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOXOR_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOAND_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOOR_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOMIN_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOMAX_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOMINU_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::AMOMAXU_W {
+                rs2,
+                rs1,
+                rd,
+                aq,
+                rl,
+            } => write_atomic(f, mnemonic, ordering(*aq, *rl), rd, rs2, rs1),
+            Operation::FLW { imm, rs1, rd } => write_memory(f, mnemonic, rd, *imm, rs1),
+            Operation::FSW { imm, rs2, rs1 } => write_memory(f, mnemonic, rs2, *imm, rs1),
+            Operation::FADD_S { rs2, rs1, rd, rm } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, rs2)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FSUB_S { rs2, rs1, rd, rm } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, rs2)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FMUL_S { rs2, rs1, rd, rm } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, rs2)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FDIV_S { rs2, rs1, rd, rm } => {
+                write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, rs2)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FSQRT_S { rs1, rd, rm } => {
+                write!(f, "{} {}, {}", mnemonic, rd, rs1)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FMADD_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => {
+                write!(f, "{} {}, {}, {}, {}", mnemonic, rd, rs1, rs2, rs3)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FMSUB_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => {
+                write!(f, "{} {}, {}, {}, {}", mnemonic, rd, rs1, rs2, rs3)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FNMSUB_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => {
+                write!(f, "{} {}, {}, {}, {}", mnemonic, rd, rs1, rs2, rs3)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::FNMADD_S {
+                rs3,
+                rs2,
+                rs1,
+                rd,
+                rm,
+            } => {
+                write!(f, "{} {}, {}, {}, {}", mnemonic, rd, rs1, rs2, rs3)?;
+                write_rounding_mode(f, rm)
+            }
+            Operation::Unknown { raw } => write!(f, "{} {:#x}", mnemonic, raw),
+        }
+    }
+}
+
+/// CSR number, printed by name if it is a standard CSR and in hex otherwise.
+pub(crate) struct Csr(pub(crate) u16);
+
+impl fmt::Display for Csr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match csr_name(self.0) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{:#x}", self.0),
+        }
+    }
+}
+
+/// The `iorw` set of a fence, printed as its letters or `0` when empty.
+struct FenceSet(u8);
+
+impl fmt::Display for FenceSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 & 0b1111 == 0 {
+            return f.write_str("0");
+        }
+        for (bit, name) in [(0b1000, 'i'), (0b0100, 'o'), (0b0010, 'r'), (0b0001, 'w')] {
+            if self.0 & bit != 0 {
+                f.write_char(name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signed pc-relative offset, printed in hex.
+pub(crate) struct Offset(pub(crate) i32);
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0 {
+            write!(f, "-{:#x}", self.0.unsigned_abs())
+        } else {
+            write!(f, "{:#x}", self.0)
+        }
+    }
+}
+
+fn write_register(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    rd: &Register,
+    rs1: &Register,
+    rs2: &Register,
+) -> fmt::Result {
+    write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, rs2)
+}
+
+fn write_unary(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    rd: &Register,
+    rs1: &Register,
+) -> fmt::Result {
+    write!(f, "{} {}, {}", mnemonic, rd, rs1)
+}
+
+fn write_immediate(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    rd: &Register,
+    rs1: &Register,
+    imm: i32,
+) -> fmt::Result {
+    write!(f, "{} {}, {}, {}", mnemonic, rd, rs1, imm)
+}
+
+fn write_memory(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    reg: &impl fmt::Display,
+    offset: i32,
+    base: &Register,
+) -> fmt::Result {
+    write!(f, "{} {}, {}({})", mnemonic, reg, offset, base)
+}
+
+fn write_branch(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    rs1: &Register,
+    rs2: &Register,
+    offset: i32,
+) -> fmt::Result {
+    write!(f, "{} {}, {}, {}", mnemonic, rs1, rs2, Offset(offset))
+}
+
+fn write_atomic(
+    f: &mut fmt::Formatter<'_>,
+    mnemonic: &str,
+    ordering: &str,
+    rd: &Register,
+    rs2: &Register,
+    rs1: &Register,
+) -> fmt::Result {
+    write!(f, "{}{} {}, {}, ({})", mnemonic, ordering, rd, rs2, rs1)
+}
+
+/// Mnemonic suffix of the acquire and release bits of an atomic, e.g. `.aqrl`.
+fn ordering(aq: bool, rl: bool) -> &'static str {
+    match (aq, rl) {
+        (false, false) => "",
+        (true, false) => ".aq",
+        (false, true) => ".rl",
+        (true, true) => ".aqrl",
+    }
+}
+
+/// Appends the rounding mode as a trailing operand. The dynamic mode is the assembler default
+/// and therefore omitted.
+fn write_rounding_mode(f: &mut fmt::Formatter<'_>, rm: &RoundingMode) -> fmt::Result {
+    match rm {
+        RoundingMode::Dyn => Ok(()),
+        rm => write!(f, ", {}", rm),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn instruction_size() {
+        let instruction_32 = Instruction {
+            width: InstructionWidth::Bit32,
+            operation: Operation::ADD {
+                rs2: Register::ZERO,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            }, //nop,
+            raw: 0x00000033,
+        };
+        assert!(instruction_32.is_32bit());
+        assert!(!instruction_32.is_16bit());
+        let instruction_16 = Instruction {
+            width: InstructionWidth::Bit16,
+            operation: Operation::ADDI {
+                imm: 0,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            }, //c.nop
+            raw: 0x0001,
+        };
+        assert!(instruction_16.is_16bit());
+        assert!(!instruction_16.is_32bit());
+    }
+
+    #[test]
+    fn width_detection() {
+        assert!(matches!(width_of(0x0513), InstructionWidth::Bit32));
+        assert!(matches!(width_of(0x0001), InstructionWidth::Bit16));
+        assert!(matches!(width_of(0x4515), InstructionWidth::Bit16));
+        assert!(matches!(width_of(0x0002), InstructionWidth::Bit16));
+        assert!(matches!(width_of(0x0000), InstructionWidth::Bit16));
+    }
+
+    #[test]
+    fn assembly() {
+        let add = Operation::ADD {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        let instruction_32 = Instruction {
+            width: InstructionWidth::Bit32,
+            operation: add,
+            raw: 0x00b50533,
+        };
+        assert_eq!(instruction_32.to_assembly(), "add a0, a0, a1");
+        let add = Operation::ADD {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        let instruction_16 = Instruction {
+            width: InstructionWidth::Bit16,
+            operation: add,
+            raw: 0x952e,
+        };
+        assert_eq!(instruction_16.to_assembly(), "c.add a0, a1");
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn compressed_assembly() {
+        let cases: [(u16, &str); 8] = [
+            (0x0001, "c.nop"),
+            (0x0505, "c.addi a0, 1"),
+            (0x4501, "c.li a0, 0"),
+            (0x852e, "c.mv a0, a1"),
+            (0x952e, "c.add a0, a1"),
+            (0x2011, "c.jal 0x4"),
+            (0x8082, "c.jr ra"),
+            (0x9502, "c.jalr a0"),
+        ];
+        for (halfword, assembly) in cases {
+            let instruction = crate::parse(&halfword.to_le_bytes()).unwrap();
+            assert_eq!(instruction.to_assembly(), assembly);
+        }
+    }
+
+    #[test]
+    fn branch_target() {
+        let forward = Operation::BEQ {
+            imm: 16,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert_eq!(forward.branch_target(0x8000), Some(0x8010));
+        let backward = Operation::BNE {
+            imm: -8,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert_eq!(backward.branch_target(0x8000), Some(0x7ff8));
+        let backward = Operation::BGEU {
+            imm: -4096,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert_eq!(backward.branch_target(0x1000), Some(0));
+        let jal = Operation::JAL {
+            rd: Register::ZERO,
+            imm: 8,
+        };
+        assert_eq!(jal.branch_target(0x8000), None);
+    }
+
+    #[test]
+    fn jump_target() {
+        let forward = Operation::JAL {
+            rd: Register::RA,
+            imm: 0x800,
+        };
+        assert_eq!(forward.jump_target(0x8000), Some(0x8800));
+        let backward = Operation::JAL {
+            rd: Register::ZERO,
+            imm: -0x100000,
+        };
+        assert_eq!(backward.jump_target(0x100000), Some(0));
+        let absolute = Operation::JALR {
+            rd: Register::RA,
+            rs1: Register::ZERO,
+            imm: 0x100,
+        };
+        assert_eq!(absolute.jump_target(0x8000), Some(0x100));
+        let indirect = Operation::JALR {
+            rd: Register::RA,
+            rs1: Register::A0,
+            imm: 0x100,
+        };
+        assert_eq!(indirect.jump_target(0x8000), None);
+    }
+
+    #[test]
+    fn predicted_next_pc() {
+        let backward = Operation::BNE {
+            imm: -8,
+            rs1: Register::A0,
+            rs2: Register::ZERO,
+        };
+        assert_eq!(
+            backward.predicted_next_pc(0x8000, &InstructionWidth::Bit32),
+            0x7ff8
+        );
+        let forward = Operation::BEQ {
+            imm: 0x20,
+            rs1: Register::A0,
+            rs2: Register::ZERO,
+        };
+        assert_eq!(
+            forward.predicted_next_pc(0x8000, &InstructionWidth::Bit32),
+            0x8004
+        );
+        assert_eq!(
+            forward.predicted_next_pc(0x8000, &InstructionWidth::Bit16),
+            0x8002
+        );
+        let jal = Operation::JAL {
+            rd: Register::RA,
+            imm: 0x20,
+        };
+        assert_eq!(
+            jal.predicted_next_pc(0x8000, &InstructionWidth::Bit32),
+            0x8020
+        );
+        let ret = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::RA,
+            imm: 0,
+        };
+        assert_eq!(
+            ret.predicted_next_pc(0x8000, &InstructionWidth::Bit32),
+            0x8004
+        );
+    }
+
+    #[test]
+    fn tail_call() {
+        // jalr zero, 0(t0)
+        let jalr = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::T0,
+            imm: 0,
+        };
+        assert!(jalr.is_tail_call());
+        // jal zero, .+8 is reported too, even though it most likely stays in the function
+        let jal = Operation::JAL {
+            rd: Register::ZERO,
+            imm: 8,
+        };
+        assert!(jal.is_tail_call());
+        // ret
+        let ret = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::RA,
+            imm: 0,
+        };
+        assert!(!ret.is_tail_call());
+        // call
+        let call = Operation::JAL {
+            rd: Register::RA,
+            imm: 8,
+        };
+        assert!(!call.is_tail_call());
+    }
+
+    #[test]
+    fn display() {
+        let add = Operation::ADD {
+            rs2: Register::T0,
+            rs1: Register::A0,
+            rd: Register::A1,
+        };
+        assert_eq!(add.to_string(), "add a1, a0, t0");
+        let addi = Operation::ADDI {
+            imm: 12,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(addi.to_string(), "addi a0, a0, 12");
+        let lw = Operation::LW {
+            imm: 8,
+            rs1: Register::SP,
+            rd: Register::A0,
+        };
+        assert_eq!(lw.to_string(), "lw a0, 8(sp)");
+        let sw = Operation::SW {
+            imm: -4,
+            rs2: Register::RA,
+            rs1: Register::SP,
+        };
+        assert_eq!(sw.to_string(), "sw ra, -4(sp)");
+        let beq = Operation::BEQ {
+            imm: 0x10,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert_eq!(beq.to_string(), "beq a0, a1, 0x10");
+        let bne = Operation::BNE {
+            imm: -8,
+            rs1: Register::A0,
+            rs2: Register::ZERO,
+        };
+        assert_eq!(bne.to_string(), "bne a0, zero, -0x8");
+        let lui = Operation::LUI {
+            rd: Register::A5,
+            imm: 0x12345000,
+        };
+        assert_eq!(lui.to_string(), "lui a5, 0x12345");
+        let csrrw = Operation::CSRRW {
+            csr: 0x300,
+            rs1: Register::A1,
+            rd: Register::A0,
+        };
+        assert_eq!(csrrw.to_string(), "csrrw a0, mstatus, a1");
+        let csrrsi = Operation::CSRRSI {
+            csr: 0x7c0,
+            zimm: 4,
+            rd: Register::ZERO,
+        };
+        assert_eq!(csrrsi.to_string(), "csrrsi zero, 0x7c0, 4");
+        let amoadd = Operation::AMOADD_W {
+            rs2: Register::A2,
+            rs1: Register::A1,
+            rd: Register::A0,
+            aq: false,
+            rl: false,
+        };
+        assert_eq!(amoadd.to_string(), "amoadd.w a0, a2, (a1)");
+        assert_eq!(Operation::FENCE_I {}.to_string(), "fence.i");
+    }
+
+    #[test]
+    fn operation_equality() {
+        let add = crate::parse(&[0xb3, 0x05, 0xa5, 0x00]).unwrap().operation;
+        assert_eq!(
+            add,
+            Operation::ADD {
+                rs2: Register::A0,
+                rs1: Register::A0,
+                rd: Register::A1,
+            }
+        );
+        assert_ne!(
+            add,
+            Operation::SUB {
+                rs2: Register::A0,
+                rs1: Register::A0,
+                rd: Register::A1,
+            }
+        );
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn instruction_display() {
+        // add a1, a0, a0
+        let instruction = crate::parse(&[0xb3, 0x05, 0xa5, 0x00]).unwrap();
+        assert_eq!(instruction.to_string(), "add a1, a0, a0");
+        // c.addi a0, 1
+        let instruction = crate::parse(&[0x05, 0x05]).unwrap();
+        assert_eq!(instruction.to_string(), "addi a0, a0, 1");
+        // addi a0, zero, 10
+        let instruction = crate::parse(&[0x13, 0x05, 0xa0, 0x00]).unwrap();
+        assert_eq!(format!("{:#}", instruction), "li a0, 10");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn operation_as_key() {
+        use std::collections::HashMap;
+        // add a1, a0, a0 twice and sub a1, a0, a0 once
+        let words: [&[u8]; 3] = [
+            &[0xb3, 0x05, 0xa5, 0x00],
+            &[0xb3, 0x05, 0xa5, 0x40],
+            &[0xb3, 0x05, 0xa5, 0x00],
+        ];
+        let mut histogram: HashMap<Operation, usize> = HashMap::new();
+        for word in words {
+            *histogram
+                .entry(crate::parse(word).unwrap().operation)
+                .or_default() += 1;
+        }
+        assert_eq!(histogram.len(), 2);
+        let add = Operation::ADD {
+            rs2: Register::A0,
+            rs1: Register::A0,
+            rd: Register::A1,
+        };
+        assert_eq!(histogram[&add], 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let instruction = crate::parse(&[0xb3, 0x05, 0xa5, 0x00]).unwrap();
+        let json = serde_json::to_string(&instruction).unwrap();
+        assert_eq!(
+            json,
+            r#"{"width":"Bit32","operation":{"ADD":{"rs2":"A0","rs1":"A0","rd":"A1"}},"raw":10814899}"#
+        );
+        let decoded: Instruction = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.operation, instruction.operation);
+    }
+
+    #[test]
+    fn mnemonic() {
+        let add = Operation::ADD {
+            rs2: Register::T0,
+            rs1: Register::A0,
+            rd: Register::A1,
+        };
+        assert_eq!(add.mnemonic(), "add");
+        let lw = Operation::LW {
+            imm: 8,
+            rs1: Register::SP,
+            rd: Register::A0,
+        };
+        assert_eq!(lw.mnemonic(), "lw");
+        let beq = Operation::BEQ {
+            imm: 16,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert_eq!(beq.mnemonic(), "beq");
+        assert_eq!(Operation::FENCE_I {}.mnemonic(), "fence.i");
+        let lr = Operation::LR_W {
+            rs1: Register::A1,
+            rd: Register::A0,
+            aq: true,
+            rl: false,
+        };
+        assert_eq!(lr.mnemonic(), "lr.w");
+    }
+
+    #[test]
+    fn classification() {
+        let beq = Operation::BEQ {
+            imm: 16,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert!(beq.is_branch() && !beq.is_jump() && !beq.is_alu());
+        let jalr = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::RA,
+            imm: 0,
+        };
+        assert!(jalr.is_jump() && !jalr.is_branch());
+        let lw = Operation::LW {
+            imm: 8,
+            rs1: Register::SP,
+            rd: Register::A0,
+        };
+        assert!(lw.is_load() && !lw.is_store());
+        let sw = Operation::SW {
+            imm: 8,
+            rs2: Register::A0,
+            rs1: Register::SP,
+        };
+        assert!(sw.is_store() && !sw.is_load());
+        let amoadd = Operation::AMOADD_W {
+            rs2: Register::A2,
+            rs1: Register::A1,
+            rd: Register::A0,
+            aq: false,
+            rl: false,
+        };
+        assert!(amoadd.is_load() && amoadd.is_store());
+        let csrrw = Operation::CSRRW {
+            csr: 0x300,
+            rs1: Register::A1,
+            rd: Register::A0,
+        };
+        assert!(csrrw.is_system() && !csrrw.is_alu());
+        assert!(!Operation::FENCE {
+            fm: 0,
+            pred: 0b1111,
+            succ: 0b1111,
+        }
+        .is_system());
+        let add = Operation::ADD {
+            rs2: Register::T0,
+            rs1: Register::A0,
+            rd: Register::A1,
+        };
+        assert!(add.is_alu() && !add.is_load() && !add.is_system());
+    }
+
+    #[test]
+    fn register_accessors() {
+        let add = Operation::ADD {
+            rs2: Register::T0,
+            rs1: Register::A0,
+            rd: Register::A1,
+        };
+        assert_eq!(add.rd(), Some(Register::A1));
+        assert_eq!(add.rs1(), Some(Register::A0));
+        assert_eq!(add.rs2(), Some(Register::T0));
+        let addi = Operation::ADDI {
+            imm: 12,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(addi.rd(), Some(Register::A0));
+        assert_eq!(addi.rs1(), Some(Register::A0));
+        assert_eq!(addi.rs2(), None);
+        let sw = Operation::SW {
+            imm: -4,
+            rs2: Register::RA,
+            rs1: Register::SP,
+        };
+        assert_eq!(sw.rd(), None);
+        assert_eq!(sw.rs1(), Some(Register::SP));
+        assert_eq!(sw.rs2(), Some(Register::RA));
+        assert_eq!(Operation::ECALL {}.rs1(), None);
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn nop() {
+        assert!(crate::parse(&0x00000013u32.to_le_bytes())
+            .unwrap()
+            .operation
+            .is_nop());
+        // c.nop expands to the same operation
+        assert!(crate::parse(&[0x01, 0x00]).unwrap().operation.is_nop());
+        // addi zero, zero, 1
+        assert!(!crate::parse(&0x00100013u32.to_le_bytes())
+            .unwrap()
+            .operation
+            .is_nop());
+    }
+
+    #[cfg(feature = "ext-m")]
+    #[test]
+    fn products() {
+        // mul, mulh, mulhsu, mulhu a0, a1, a2
+        let [mul, mulh, mulhsu, mulhu] = [0x02c58533u32, 0x02c59533, 0x02c5a533, 0x02c5b533]
+            .map(|word| crate::parse(&word.to_le_bytes()).unwrap().operation);
+        assert_eq!(mul.product_is_high(), Some(false));
+        assert_eq!(mul.product_signedness(), None);
+        assert_eq!(mulh.product_is_high(), Some(true));
+        assert_eq!(mulh.product_signedness(), Some(Signedness::Signed));
+        assert_eq!(mulhsu.product_is_high(), Some(true));
+        assert_eq!(
+            mulhsu.product_signedness(),
+            Some(Signedness::SignedUnsigned)
+        );
+        assert_eq!(mulhu.product_is_high(), Some(true));
+        assert_eq!(mulhu.product_signedness(), Some(Signedness::Unsigned));
+        let add = Operation::ADD {
+            rs2: Register::A2,
+            rs1: Register::A1,
+            rd: Register::A0,
+        };
+        assert_eq!(add.product_is_high(), None);
+        assert_eq!(add.product_signedness(), None);
+    }
+
+    #[cfg(feature = "ext-m")]
+    #[test]
+    fn divisions() {
+        // div, divu, rem, remu a0, a1, a2
+        for word in [0x02c5c533u32, 0x02c5d533, 0x02c5e533, 0x02c5f533] {
+            assert!(crate::parse(&word.to_le_bytes())
+                .unwrap()
+                .operation
+                .is_division());
+        }
+        let mul = crate::parse(&0x02c58533u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(!mul.is_division());
+    }
+
+    #[cfg(feature = "ext-a")]
+    #[test]
+    fn memory_effects() {
+        // lw a0, 8(sp)
+        let lw = crate::parse(&0x00812503u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(lw.reads_memory() && !lw.writes_memory());
+        // sw ra, 12(sp)
+        let sw = crate::parse(&0x00112623u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(sw.writes_memory() && !sw.reads_memory());
+        // amoadd.w a0, a2, (a1)
+        let amoadd = crate::parse(&0x00c5a52fu32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(amoadd.reads_memory() && amoadd.writes_memory());
+        // sc.w a0, a2, (a1)
+        let sc = crate::parse(&0x18c5a52fu32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(sc.writes_memory() && !sc.reads_memory());
+        // add a0, a0, a1
+        let add = crate::parse(&0x00b50533u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(!add.reads_memory() && !add.writes_memory());
+    }
+
+    #[test]
+    fn successors() {
+        let beq = Operation::BEQ {
+            imm: -8,
+            rs1: Register::A0,
+            rs2: Register::A1,
+        };
+        assert!(beq.is_control_flow());
+        assert_eq!(
+            beq.successors(0x8000, &InstructionWidth::Bit32),
+            vec![0x8004, 0x7ff8]
+        );
+        let add = Operation::ADD {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert!(!add.is_control_flow());
+        assert_eq!(
+            add.successors(0x8000, &InstructionWidth::Bit16),
+            vec![0x8002]
+        );
+        let jal = Operation::JAL {
+            rd: Register::RA,
+            imm: 0x100,
+        };
+        assert_eq!(
+            jal.successors(0x8000, &InstructionWidth::Bit32),
+            vec![0x8100]
+        );
+        let ret = Operation::JALR {
+            rd: Register::ZERO,
+            rs1: Register::RA,
+            imm: 0,
+        };
+        assert!(ret.is_control_flow());
+        assert!(ret.successors(0x8000, &InstructionWidth::Bit32).is_empty());
+        assert!(Operation::ECALL {}.is_control_flow());
+        assert_eq!(
+            Operation::ECALL {}.successors(0x8000, &InstructionWidth::Bit32),
+            vec![0x8004]
+        );
+        // a branch to the next instruction has a single successor
+        let skip = Operation::BNE {
+            imm: 4,
+            rs1: Register::A0,
+            rs2: Register::ZERO,
+        };
+        assert_eq!(
+            skip.successors(0x8000, &InstructionWidth::Bit32),
+            vec![0x8004]
+        );
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn calls_and_returns() {
+        // jal ra, 0x100
+        let call = crate::parse(&0x100000efu32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(call.is_call() && !call.is_return());
+        // jal zero, -8
+        let jump = crate::parse(&0xff9ff06fu32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(!jump.is_call() && !jump.is_return());
+        // jalr zero, 0(ra)
+        let ret = crate::parse(&0x00008067u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(ret.is_return() && !ret.is_call());
+        // jalr ra, 0(a0)
+        let indirect = crate::parse(&0x000500e7u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert!(indirect.is_call());
+        // c.jr ra
+        let compressed = crate::parse(&[0x82, 0x80]).unwrap().operation;
+        assert!(compressed.is_return());
+    }
+
+    #[cfg(all(feature = "ext-a", feature = "ext-f"))]
+    #[test]
+    fn kinds() {
+        for (word, kind) in [
+            (0x00b50533u32, InstructionKind::Arithmetic), // add a0, a0, a1
+            (0x00b54533, InstructionKind::Logical),       // xor a0, a0, a1
+            (0x00151513, InstructionKind::Shift),         // slli a0, a0, 1
+            (0x00812503, InstructionKind::Load),          // lw a0, 8(sp)
+            (0x00112623, InstructionKind::Store),         // sw ra, 12(sp)
+            (0x00b50863, InstructionKind::Branch),        // beq a0, a1, 16
+            (0x00008067, InstructionKind::Jump),          // ret
+            (0x00000073, InstructionKind::System),        // ecall
+            (0x30002573, InstructionKind::Csr),           // csrr a0, mstatus
+            (0x00c5f553, InstructionKind::FloatingPoint), // fadd.s fa0, fa1, fa2
+            (0x00c5a52f, InstructionKind::Atomic),        // amoadd.w a0, a2, (a1)
+        ] {
+            assert_eq!(
+                crate::parse(&word.to_le_bytes()).unwrap().operation.kind(),
+                kind
+            );
+        }
+        assert_eq!(
+            crate::parse_lenient(&[0xff, 0xff, 0xff, 0xff])
+                .operation
+                .kind(),
+            InstructionKind::Unknown
+        );
     }
 }
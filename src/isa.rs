@@ -0,0 +1,243 @@
+//! Provides configuration of the instruction set extensions the parser decodes.
+
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::instructons::{Instruction, InstructionWidth, Operation};
+
+/// Native register width of the decoded instruction set.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Xlen {
+    /// RV32I.
+    #[default]
+    Rv32,
+    /// RV64I. Adds the word operations, `lwu`, `ld`, `sd` and 6 bit shift amounts.
+    Rv64,
+}
+
+/// Selects which optional extensions are decoded. Encodings belonging to a disabled
+/// extension are reported as invalid.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct IsaConfig {
+    /// Base instruction set.
+    pub xlen: Xlen,
+    /// Carry-less multiplication (`clmul`, `clmulh`, `clmulr`).
+    pub zbc: bool,
+    /// Address generation (`sh1add`, `sh2add`, `sh3add`).
+    pub zba: bool,
+    /// Basic bit manipulation (`andn`, `clz`, `min`, `rol`, `rev8`, ...).
+    pub zbb: bool,
+    /// Single-bit instructions (`bclr`, `bext`, `binv`, `bset` and their immediate forms).
+    pub zbs: bool,
+    /// Conditional zeroing (`czero.eqz`, `czero.nez`).
+    pub zicond: bool,
+    /// Single-precision floating point (`fadd.s`, `fsqrt.s`, ...).
+    pub f: bool,
+}
+
+impl Default for IsaConfig {
+    /// Enables everything the parser supports on RV32.
+    fn default() -> Self {
+        IsaConfig {
+            xlen: Xlen::Rv32,
+            zbc: true,
+            zba: true,
+            zbb: true,
+            zbs: true,
+            zicond: true,
+            f: true,
+        }
+    }
+}
+
+/// Set of standard extensions, like a `-march` string. Combine flags with `|`, e.g.
+/// `InstructionSet::I | InstructionSet::M | InstructionSet::C` for RV32IMC.
+///
+/// The CSR instructions and `fence.i` are counted as part of `I`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct InstructionSet(u32);
+
+impl InstructionSet {
+    /// Base integer instructions.
+    pub const I: InstructionSet = InstructionSet(1 << 0);
+    /// Integer multiplication and division.
+    pub const M: InstructionSet = InstructionSet(1 << 1);
+    /// Atomics.
+    pub const A: InstructionSet = InstructionSet(1 << 2);
+    /// Single-precision floating point.
+    pub const F: InstructionSet = InstructionSet(1 << 3);
+    /// Double-precision floating point.
+    pub const D: InstructionSet = InstructionSet(1 << 4);
+    /// Compressed instructions.
+    pub const C: InstructionSet = InstructionSet(1 << 5);
+    /// Address generation.
+    pub const ZBA: InstructionSet = InstructionSet(1 << 6);
+    /// Basic bit manipulation.
+    pub const ZBB: InstructionSet = InstructionSet(1 << 7);
+    /// Carry-less multiplication.
+    pub const ZBC: InstructionSet = InstructionSet(1 << 8);
+    /// Single-bit instructions.
+    pub const ZBS: InstructionSet = InstructionSet(1 << 9);
+    /// Conditional zeroing.
+    pub const ZICOND: InstructionSet = InstructionSet(1 << 10);
+
+    /// No extensions at all.
+    pub const fn empty() -> InstructionSet {
+        InstructionSet(0)
+    }
+
+    /// Every extension listed above.
+    pub const fn all() -> InstructionSet {
+        InstructionSet((1 << 11) - 1)
+    }
+
+    /// Whether all extensions in `other` are also in `self`.
+    pub const fn contains(&self, other: InstructionSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The extensions in either set.
+    pub const fn union(self, other: InstructionSet) -> InstructionSet {
+        InstructionSet(self.0 | other.0)
+    }
+}
+
+impl BitOr for InstructionSet {
+    type Output = InstructionSet;
+    fn bitor(self, other: InstructionSet) -> InstructionSet {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for InstructionSet {
+    fn bitor_assign(&mut self, other: InstructionSet) {
+        *self = self.union(other);
+    }
+}
+
+impl Operation {
+    /// The extension the operation belongs to. Whether the operation was compressed is not
+    /// known here, see [`Instruction::extensions`] for that.
+    pub fn extension(&self) -> InstructionSet {
+        match self {
+            Operation::MUL { .. }
+            | Operation::MULH { .. }
+            | Operation::MULHSU { .. }
+            | Operation::MULHU { .. }
+            | Operation::DIV { .. }
+            | Operation::DIVU { .. }
+            | Operation::REM { .. }
+            | Operation::REMU { .. } => InstructionSet::M,
+            Operation::LR_W { .. }
+            | Operation::SC_W { .. }
+            | Operation::AMOSWAP_W { .. }
+            | Operation::AMOADD_W { .. }
+            | Operation::AMOXOR_W { .. }
+            | Operation::AMOAND_W { .. }
+            | Operation::AMOOR_W { .. }
+            | Operation::AMOMIN_W { .. }
+            | Operation::AMOMAX_W { .. }
+            | Operation::AMOMINU_W { .. }
+            | Operation::AMOMAXU_W { .. } => InstructionSet::A,
+            Operation::FLW { .. }
+            | Operation::FSW { .. }
+            | Operation::FADD_S { .. }
+            | Operation::FSUB_S { .. }
+            | Operation::FMUL_S { .. }
+            | Operation::FDIV_S { .. }
+            | Operation::FSQRT_S { .. }
+            | Operation::FMADD_S { .. }
+            | Operation::FMSUB_S { .. }
+            | Operation::FNMSUB_S { .. }
+            | Operation::FNMADD_S { .. } => InstructionSet::F,
+            Operation::SH1ADD { .. } | Operation::SH2ADD { .. } | Operation::SH3ADD { .. } => {
+                InstructionSet::ZBA
+            }
+            Operation::ANDN { .. }
+            | Operation::ORN { .. }
+            | Operation::XNOR { .. }
+            | Operation::MIN { .. }
+            | Operation::MINU { .. }
+            | Operation::MAX { .. }
+            | Operation::MAXU { .. }
+            | Operation::ROL { .. }
+            | Operation::ROR { .. }
+            | Operation::RORI { .. }
+            | Operation::CLZ { .. }
+            | Operation::CTZ { .. }
+            | Operation::CPOP { .. }
+            | Operation::SEXT_B { .. }
+            | Operation::SEXT_H { .. }
+            | Operation::REV8 { .. }
+            | Operation::ORC_B { .. }
+            | Operation::ZEXT_H { .. } => InstructionSet::ZBB,
+            Operation::CLMUL { .. } | Operation::CLMULH { .. } | Operation::CLMULR { .. } => {
+                InstructionSet::ZBC
+            }
+            Operation::BCLR { .. }
+            | Operation::BEXT { .. }
+            | Operation::BINV { .. }
+            | Operation::BSET { .. }
+            | Operation::BCLRI { .. }
+            | Operation::BEXTI { .. }
+            | Operation::BINVI { .. }
+            | Operation::BSETI { .. } => InstructionSet::ZBS,
+            Operation::CZERO_EQZ { .. } | Operation::CZERO_NEZ { .. } => InstructionSet::ZICOND,
+            // not a decoded instruction, so nothing is required
+            Operation::Unknown { .. } => InstructionSet::empty(),
+            _ => InstructionSet::I,
+        }
+    }
+}
+
+impl Instruction {
+    /// The extensions needed to execute the instruction: the extension of its operation, and
+    /// `C` as well if it is compressed.
+    pub fn extensions(&self) -> InstructionSet {
+        match self.width {
+            InstructionWidth::Bit16 => self.operation.extension() | InstructionSet::C,
+            InstructionWidth::Bit32 => self.operation.extension(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registers::Register;
+
+    #[test]
+    fn instruction_set() {
+        let mut rv32imc = InstructionSet::I | InstructionSet::M;
+        rv32imc |= InstructionSet::C;
+        assert!(rv32imc.contains(InstructionSet::M));
+        assert!(rv32imc.contains(InstructionSet::I | InstructionSet::C));
+        assert!(!rv32imc.contains(InstructionSet::A));
+        assert!(rv32imc.contains(InstructionSet::empty()));
+        assert!(InstructionSet::all().contains(rv32imc | InstructionSet::ZICOND));
+    }
+
+    #[test]
+    fn extension() {
+        let mul = Operation::MUL {
+            rs2: Register::A2,
+            rs1: Register::A1,
+            rd: Register::A0,
+        };
+        assert_eq!(mul.extension(), InstructionSet::M);
+        let addi = Operation::ADDI {
+            imm: 1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        };
+        assert_eq!(addi.extension(), InstructionSet::I);
+        let compressed = Instruction {
+            width: InstructionWidth::Bit16,
+            operation: addi,
+            raw: 0x0505,
+        };
+        assert_eq!(
+            compressed.extensions(),
+            InstructionSet::I | InstructionSet::C
+        );
+    }
+}
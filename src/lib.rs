@@ -13,175 +13,543 @@
 //!     }
 //! # }
 //! ```
+//!
+//! # Features
+//! - `std` (default): implements `std::error::Error` for [`error::ParseError`] and adds
+//!   `reader::parse_reader` for parsing from a `std::io::Read` stream. Without it the crate is
+//!   `no_std` and only needs `alloc`.
+//! - `serde`: derives `Serialize`/`Deserialize` for the instruction types.
+//! - `elf`: adds `elf::disassemble_text` for disassembling ELF files, using the `object`
+//!   crate.
+//! - `ext-m`, `ext-a`, `ext-f`, `ext-c` (default): decode the M, A, F and C extensions. Turning
+//!   one off compiles its decoder out, and its encodings then fail to parse as if they were
+//!   invalid. The `Operation` variants stay, so that code matching on them builds with any
+//!   set of features. The base integer instruction set is always decoded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+pub mod analysis;
+pub mod assembler;
+pub mod cache;
 pub mod conditions;
+pub mod csr;
+pub mod disassembler;
+#[cfg(feature = "elf")]
+pub mod elf;
+pub mod encoder;
+pub mod error;
+pub mod fields;
+pub mod immediates;
 pub mod instructons;
+pub mod isa;
+pub mod lint;
+pub mod pseudo;
+#[cfg(feature = "std")]
+pub mod reader;
 pub mod registers;
+pub mod rounding;
+
+use alloc::vec::Vec;
 
+use error::{ParseError, ParseErrorAt};
+use immediates::{decode_b_imm, decode_i_imm, decode_j_imm, decode_s_imm, sign_extend};
 use instructons::*;
+use isa::{InstructionSet, IsaConfig, Xlen};
+#[cfg(feature = "ext-f")]
+use registers::FloatRegister;
+use registers::Register;
+#[cfg(feature = "ext-f")]
+use rounding::RoundingMode;
 
 /// This function parses a input byte slice into one instruction.
-/// Returns Err(&str) if instruction is invalid.
-pub fn parse(input: &[u8]) -> Result<Instruction, String> {
+/// Returns Err(ParseError) if instruction is invalid.
+pub fn parse(input: &[u8]) -> Result<Instruction, ParseError> {
+    parse_with_config(input, &IsaConfig::default())
+}
+
+/// Like [`parse`], but also returns the absolute target of a conditional branch or `jal`
+/// located at `pc`. The target is None for all other operations, including `jalr`.
+pub fn parse_with_pc(input: &[u8], pc: u32) -> Result<(Instruction, Option<u32>), ParseError> {
+    let instruction = parse(input)?;
+    let target = analysis::static_target(&instruction.operation, pc);
+    Ok((instruction, target))
+}
+
+/// Parses the instruction at the start of `input`, returning it with the number of bytes it
+/// takes up, 2 or 4. Lets callers step through a buffer by hand.
+pub fn decode_one(input: &[u8]) -> Result<(Instruction, usize), ParseError> {
+    let instruction = parse(input)?;
+    Ok((instruction, instruction.width.bytes() as usize))
+}
+
+/// Parses every instruction in `input`, failing on the first one that can not be parsed with
+/// the error and the byte offset of that instruction. A trailing incomplete instruction fails
+/// with [`ParseError::Truncated`].
+pub fn parse_all(input: &[u8]) -> Result<Vec<Instruction>, ParseErrorAt> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < input.len() {
+        let (instruction, length) =
+            decode_one(&input[offset..]).map_err(|error| ParseErrorAt { offset, error })?;
+        instructions.push(instruction);
+        offset += length;
+    }
+    Ok(instructions)
+}
+
+/// Like [`parse`], but only decodes the extensions enabled in `config`.
+pub fn parse_with_config(input: &[u8], config: &IsaConfig) -> Result<Instruction, ParseError> {
+    if input.len() < 2 {
+        return Err(ParseError::Truncated);
+    }
+    let width = width_of(<u16>::from_le_bytes([input[0], input[1]]));
+    if input.len() < width.bytes() as usize {
+        return Err(ParseError::Truncated);
+    }
+    let raw = raw_bits(input, &width);
+    let operation = match width {
+        InstructionWidth::Bit32 => parse_32bit_operation_with(raw, config),
+        InstructionWidth::Bit16 => parse_compressed(raw as u16),
+    }
+    .map_err(|error| error.with_word(raw))?;
     Ok(Instruction {
-        width: InstructionWidth::Bit32,
-        operation: parse_32bit_operation(<u32>::from_le_bytes([
-            input[0], input[1], input[2], input[3],
-        ]))?,
+        width,
+        operation,
+        raw,
     })
 }
-use bitutils::sign_extend32;
-fn parse_32bit_operation(instruction: u32) -> Result<Operation, String> {
+
+/// Like [`parse`], but reads `input` as one big-endian 32 bit word, as found in word-oriented
+/// memory dumps. A compressed instruction is taken from the low halfword of that word, so
+/// `input` always has to hold four bytes.
+pub fn parse_be(input: &[u8]) -> Result<Instruction, ParseError> {
+    parse_be_with_config(input, &IsaConfig::default())
+}
+
+/// Like [`parse_be`], but only decodes the extensions enabled in `config`.
+pub fn parse_be_with_config(input: &[u8], config: &IsaConfig) -> Result<Instruction, ParseError> {
+    if input.len() < 4 {
+        return Err(ParseError::Truncated);
+    }
+    let word = <u32>::from_be_bytes([input[0], input[1], input[2], input[3]]);
+    parse_with_config(&word.to_le_bytes(), config)
+}
+
+/// Like [`parse`], but fails with [`ParseError::DisabledExtension`] if the instruction needs an
+/// extension that is not in `isa`. Useful for rejecting data mis-decoded as code when the
+/// target's `-march` is known.
+pub fn parse_with_isa(input: &[u8], isa: InstructionSet) -> Result<Instruction, ParseError> {
+    let instruction = parse(input)?;
+    if isa.contains(instruction.extensions()) {
+        Ok(instruction)
+    } else {
+        Err(ParseError::DisabledExtension {
+            word: instruction.raw,
+        })
+    }
+}
+
+/// Like [`parse`], but never fails on an unrecognized encoding. Instead it is returned as
+/// [`Operation::Unknown`] holding the raw bits, so that scanning a binary can continue past it.
+///
+/// Input shorter than the instruction it starts is returned as `Unknown` as well, with the
+/// missing bytes read as zero. Empty input gives a 16 bit `Unknown { raw: 0 }`.
+pub fn parse_lenient(input: &[u8]) -> Instruction {
+    parse_lenient_with_config(input, &IsaConfig::default())
+}
+
+/// Like [`parse_lenient`], but only decodes the extensions enabled in `config`.
+pub fn parse_lenient_with_config(input: &[u8], config: &IsaConfig) -> Instruction {
+    parse_with_config(input, config).unwrap_or_else(|_| {
+        let mut bytes = [0; 4];
+        let available = input.len().min(4);
+        bytes[..available].copy_from_slice(&input[..available]);
+        let width = width_of(<u16>::from_le_bytes([bytes[0], bytes[1]]));
+        let raw = raw_bits(&bytes, &width);
+        Instruction {
+            width,
+            operation: Operation::Unknown { raw },
+            raw,
+        }
+    })
+}
+
+/// The little-endian instruction bits of the given width at the start of `input`.
+fn raw_bits(input: &[u8], width: &InstructionWidth) -> u32 {
+    match width {
+        InstructionWidth::Bit32 => <u32>::from_le_bytes([input[0], input[1], input[2], input[3]]),
+        InstructionWidth::Bit16 => <u16>::from_le_bytes([input[0], input[1]]) as u32,
+    }
+}
+
+/// Parses a compressed instruction, which fails unless the `ext-c` feature is enabled.
+#[cfg(feature = "ext-c")]
+fn parse_compressed(instruction: u16) -> Result<Operation, ParseError> {
+    parse_16bit_operation(instruction)
+}
+
+#[cfg(not(feature = "ext-c"))]
+fn parse_compressed(instruction: u16) -> Result<Operation, ParseError> {
+    // the all-zero halfword is illegal whether or not C is decoded
+    if instruction == 0 {
+        return Err(ParseError::Illegal);
+    }
+    Err(ParseError::DisabledExtension {
+        word: instruction as u32,
+    })
+}
+
+/// Parses a compressed instruction into the operation it expands to.
+#[cfg_attr(not(feature = "ext-c"), allow(dead_code))]
+fn parse_16bit_operation(instruction: u16) -> Result<Operation, ParseError> {
+    if instruction == 0 {
+        return Err(ParseError::Illegal);
+    }
+    let quadrant = instruction & 0b11;
+    let funct3 = (instruction & (0b111 << 13)) >> 13;
+    match quadrant {
+        0b01 => {
+            //C1
+            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = sign_extend(
+                (((instruction & (0b1 << 12)) >> 7) | ((instruction & (0b11111 << 2)) >> 2)) as u32,
+                6,
+            );
+            match funct3 {
+                0b000 => {
+                    //C.ADDI, C.NOP when rd is zero
+                    Ok(Operation::ADDI { imm, rs1: rd, rd })
+                }
+                0b001 => {
+                    //C.JAL
+                    let offset = ((instruction & (0b1 << 12)) >> 1)
+                        | ((instruction & (0b1 << 11)) >> 7)
+                        | ((instruction & (0b11 << 9)) >> 1)
+                        | ((instruction & (0b1 << 8)) << 2)
+                        | ((instruction & (0b1 << 7)) >> 1)
+                        | ((instruction & (0b1 << 6)) << 1)
+                        | ((instruction & (0b111 << 3)) >> 2)
+                        | ((instruction & (0b1 << 2)) << 3);
+                    Ok(Operation::JAL {
+                        rd: Register::RA,
+                        imm: sign_extend(offset as u32, 12),
+                    })
+                }
+                0b010 => {
+                    //C.LI
+                    Ok(Operation::ADDI {
+                        imm,
+                        rs1: Register::ZERO,
+                        rd,
+                    })
+                }
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction as u32,
+                    funct3: funct3 as u32,
+                }),
+            }
+        }
+        0b10 => {
+            //C2
+            let rs1: Register = (((instruction & (0b11111 << 7)) >> 7) as u8)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs2: Register = (((instruction & (0b11111 << 2)) >> 2) as u8)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let bit12 = instruction & (0b1 << 12) != 0;
+            match funct3 {
+                0b100 => match (bit12, rs1, rs2) {
+                    (false, Register::ZERO, Register::ZERO) => Err(ParseError::Reserved {
+                        word: instruction as u32,
+                    }),
+                    (false, rs1, Register::ZERO) => Ok(Operation::JALR {
+                        rd: Register::ZERO,
+                        rs1,
+                        imm: 0,
+                    }), //C.JR
+                    (false, rd, rs2) => Ok(Operation::ADD {
+                        rs2,
+                        rs1: Register::ZERO,
+                        rd,
+                    }), //C.MV
+                    (true, Register::ZERO, Register::ZERO) => Ok(Operation::EBREAK {}), //C.EBREAK
+                    (true, rs1, Register::ZERO) => Ok(Operation::JALR {
+                        rd: Register::RA,
+                        rs1,
+                        imm: 0,
+                    }), //C.JALR
+                    (true, rd, rs2) => Ok(Operation::ADD { rs2, rs1: rd, rd }),         //C.ADD
+                },
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction as u32,
+                    funct3: funct3 as u32,
+                }),
+            }
+        }
+        _ => Err(ParseError::InvalidOpcode {
+            word: instruction as u32,
+            opcode: quadrant as u32,
+        }),
+    }
+}
+#[cfg(test)]
+fn parse_32bit_operation(instruction: u32) -> Result<Operation, ParseError> {
+    parse_32bit_operation_with(instruction, &IsaConfig::default())
+}
+
+fn parse_32bit_operation_with(
+    instruction: u32,
+    config: &IsaConfig,
+) -> Result<Operation, ParseError> {
+    if instruction == 0 {
+        return Err(ParseError::Illegal);
+    }
     let opcode = instruction & 0b1111111;
     let funct3 = (instruction & (0b111 << 12)) >> 12;
     let funct7 = (instruction & (0b1111111 << 25)) >> 25;
-    let imm = sign_extend32(instruction >> 20, 12);
-    let shamt = (instruction & (0b11111 << 20)) >> 20;
-    let imm_big = instruction & 0xFFFFF000;
-    let imm_big_shuffled = sign_extend32(
-        (((instruction & (0b1 << 31)) >> (31 - 20))
-            | ((instruction & (0b1111111111 << 21)) >> (30 - 10))
-            | ((instruction & (0b1 << 20)) >> (20 - 11))
-            | (instruction & (0b11111111 << 12)))
-            & 0b1111_1111_1111_1111_1111_1111_1111_1110,
-        21,
-    );
-    //no idea why this is encoded this way but the ISA is what it is
-    let imm_store =
-        ((instruction & (0b11111 << 7)) >> 7) | ((instruction & (0b1111111 << 25)) >> 20);
+    // immediates are only extracted in the arms of the formats that have them
     match opcode {
         0b0110011 => {
             //OP                                         //rs1 [19:15] rs2 [24:20] rd [11:7]
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8).try_into()?;
-            let rs2 = (((instruction & (0b11111 << 20)) >> 20) as u8).try_into()?;
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = ((instruction & (0b11111 << 20)) >> 20)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
             match funct3 {
                 0b000 => {
                     // add/sub
                     match funct7 {
                         0b0000000 => Ok(Operation::ADD { rs1, rs2, rd }), //add
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::MUL { rs1, rs2, rd }), //mul
                         0b0100000 => Ok(Operation::SUB { rs1, rs2, rd }), //sub
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b001 => {
                     match funct7 {
                         // sll
                         0b0000000 => Ok(Operation::SLL { rs1, rs2, rd }), //sll
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::MULH { rs1, rs2, rd }), //mulh
+                        0b0110000 if config.zbb => Ok(Operation::ROL { rs1, rs2, rd }), //rol
+                        0b0100100 if config.zbs => Ok(Operation::BCLR { rs1, rs2, rd }), //bclr
+                        0b0110100 if config.zbs => Ok(Operation::BINV { rs1, rs2, rd }), //binv
+                        0b0010100 if config.zbs => Ok(Operation::BSET { rs1, rs2, rd }), //bset
+                        0b0000101 if config.zbc => Ok(Operation::CLMUL { rs1, rs2, rd }), //clmul
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b010 => {
                     match funct7 {
                         // slt
                         0b0000000 => Ok(Operation::SLT { rs1, rs2, rd }), //slt
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::MULHSU { rs1, rs2, rd }), //mulhsu
+                        0b0000101 if config.zbc => Ok(Operation::CLMULR { rs1, rs2, rd }), //clmulr
+                        0b0010000 if config.zba => Ok(Operation::SH1ADD { rs1, rs2, rd }), //sh1add
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b011 => {
                     match funct7 {
                         // sltu
                         0b0000000 => Ok(Operation::SLTU { rs1, rs2, rd }), //sltu
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::MULHU { rs1, rs2, rd }), //mulhu
+                        0b0000101 if config.zbc => Ok(Operation::CLMULH { rs1, rs2, rd }), //clmulh
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b100 => {
                     match funct7 {
                         // xor
                         0b0000000 => Ok(Operation::XOR { rs1, rs2, rd }), //xor
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::DIV { rs1, rs2, rd }), //div
+                        0b0100000 if config.zbb => Ok(Operation::XNOR { rs1, rs2, rd }), //xnor
+                        0b0000101 if config.zbb => Ok(Operation::MIN { rs1, rs2, rd }), //min
+                        0b0000100
+                            if config.zbb && config.xlen == Xlen::Rv32 && rs2 == Register::ZERO =>
+                        {
+                            Ok(Operation::ZEXT_H { rs1, rd }) //zext.h
+                        }
+                        0b0010000 if config.zba => Ok(Operation::SH2ADD { rs1, rs2, rd }), //sh2add
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b101 => {
                     match funct7 {
                         // srl
                         0b0000000 => Ok(Operation::SRL { rs1, rs2, rd }), //srl
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::DIVU { rs1, rs2, rd }), //divu
                         0b0100000 => Ok(Operation::SRA { rs1, rs2, rd }), //sra
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        0b0000101 if config.zbb => Ok(Operation::MINU { rs1, rs2, rd }), //minu
+                        0b0110000 if config.zbb => Ok(Operation::ROR { rs1, rs2, rd }), //ror
+                        0b0100100 if config.zbs => Ok(Operation::BEXT { rs1, rs2, rd }), //bext
+                        0b0000111 if config.zicond => {
+                            Ok(Operation::CZERO_EQZ { rs1, rs2, rd }) //czero.eqz
+                        }
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b110 => {
                     match funct7 {
                         // or
                         0b0000000 => Ok(Operation::OR { rs1, rs2, rd }), //or
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::REM { rs1, rs2, rd }), //rem
+                        0b0100000 if config.zbb => Ok(Operation::ORN { rs1, rs2, rd }), //orn
+                        0b0000101 if config.zbb => Ok(Operation::MAX { rs1, rs2, rd }), //max
+                        0b0010000 if config.zba => Ok(Operation::SH3ADD { rs1, rs2, rd }), //sh3add
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
                 0b111 => {
                     //and
                     match funct7 {
                         0b0000000 => Ok(Operation::AND { rs1, rs2, rd }), //and
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        #[cfg(feature = "ext-m")]
+                        0b0000001 => Ok(Operation::REMU { rs1, rs2, rd }), //remu
+                        0b0100000 if config.zbb => Ok(Operation::ANDN { rs1, rs2, rd }), //andn
+                        0b0000101 if config.zbb => Ok(Operation::MAXU { rs1, rs2, rd }), //maxu
+                        0b0000111 if config.zicond => {
+                            Ok(Operation::CZERO_NEZ { rs1, rs2, rd }) //czero.nez
+                        }
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
-                _ => Err(format!("Invalid funct3 {:b}", funct3)),
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
             }
         }
         0b0010011 => {
             //OP_IMM
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8).try_into()?;
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_i_imm(instruction);
+            let shamt = (instruction & (0b11111 << 20)) >> 20;
+            // on RV64 the lowest bit of funct7 is the top bit of the shift amount, on RV32 it
+            // must be zero
+            let (shamt, shift_funct7) = match config.xlen {
+                Xlen::Rv32 => (shamt, funct7),
+                Xlen::Rv64 => (shamt | ((funct7 & 0b1) << 5), funct7 & !0b1),
+            };
+            // rev8 reverses all bytes of the register, so its encoding depends on XLEN
+            let rev8 = match config.xlen {
+                Xlen::Rv32 => 0x698,
+                Xlen::Rv64 => 0x6b8,
+            };
             //trace!("opcode=OP_IMM");
             match funct3 {
                 0b000 => {
                     //ADDI
-                    Ok(Operation::ADDI {
-                        imm: imm as i32,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::ADDI { imm, rs1, rd })
                 }
                 0b010 => {
                     //SLTI
-                    Ok(Operation::SLTI {
-                        imm: imm as u16,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::SLTI { imm, rs1, rd })
                 }
                 0b011 => {
                     //SLTIU
-                    Ok(Operation::SLTIU {
-                        imm: imm as u16,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::SLTIU { imm, rs1, rd })
                 }
                 0b100 => {
                     //XORI
-                    Ok(Operation::XORI {
-                        imm: imm as u16,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::XORI { imm, rs1, rd })
                 }
                 0b110 => {
                     //ORI
-                    Ok(Operation::ORI {
-                        imm: imm as u16,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::ORI { imm, rs1, rd })
                 }
                 0b111 => {
                     //ANDI
-                    Ok(Operation::ANDI {
-                        imm: imm as u16,
-                        rs1,
-                        rd,
-                    })
+                    Ok(Operation::ANDI { imm, rs1, rd })
                 }
                 0b001 => {
                     //SLLI
-                    Ok(Operation::SLLI {
-                        shamt: shamt as u8,
-                        rs1,
-                        rd,
-                    })
+                    match shift_funct7 {
+                        0b0000000 => Ok(Operation::SLLI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }),
+                        0b0110000 if config.zbb => match instruction >> 20 {
+                            0x600 => Ok(Operation::CLZ { rs1, rd }),    //clz
+                            0x601 => Ok(Operation::CTZ { rs1, rd }),    //ctz
+                            0x602 => Ok(Operation::CPOP { rs1, rd }),   //cpop
+                            0x604 => Ok(Operation::SEXT_B { rs1, rd }), //sext.b
+                            0x605 => Ok(Operation::SEXT_H { rs1, rd }), //sext.h
+                            funct12 => Err(ParseError::InvalidFunct12 {
+                                word: instruction,
+                                funct12,
+                            }),
+                        },
+                        0b0100100 if config.zbs => Ok(Operation::BCLRI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }), //BCLRI
+                        0b0110100 if config.zbs => Ok(Operation::BINVI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }), //BINVI
+                        0b0010100 if config.zbs => Ok(Operation::BSETI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }), //BSETI
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
+                    }
                 }
                 0b101 => {
                     //SRLI SRAI
-                    match funct7 {
+                    match shift_funct7 {
                         0b0000000 => Ok(Operation::SRLI {
                             shamt: shamt as u8,
                             rs1,
@@ -192,108 +560,148 @@ fn parse_32bit_operation(instruction: u32) -> Result<Operation, String> {
                             rs1,
                             rd,
                         }), //SRAI
-                        _ => Err(format!("Invalid funct7 {:b}", funct7)),
+                        0b0110000 if config.zbb => Ok(Operation::RORI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }), //RORI
+                        0b0010100 if config.zbb && instruction >> 20 == 0x287 => {
+                            Ok(Operation::ORC_B { rs1, rd }) //orc.b
+                        }
+                        0b0110100 if config.zbb && instruction >> 20 == rev8 => {
+                            Ok(Operation::REV8 { rs1, rd }) //rev8
+                        }
+                        0b0100100 if config.zbs => Ok(Operation::BEXTI {
+                            shamt: shamt as u8,
+                            rs1,
+                            rd,
+                        }), //BEXTI
+                        _ => Err(ParseError::InvalidFunct7 {
+                            word: instruction,
+                            funct7,
+                        }),
                     }
                 }
-                _ => Err(format!("Invalid funct3 {:b}", funct3)),
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
+            }
+        }
+        0b0011011 if config.xlen == Xlen::Rv64 => {
+            //OP_IMM_32
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_i_imm(instruction);
+            let shamt = ((instruction & (0b11111 << 20)) >> 20) as u8;
+            match (funct3, funct7) {
+                (0b000, _) => Ok(Operation::ADDIW { imm, rs1, rd }), //addiw
+                (0b001, 0b0000000) => Ok(Operation::SLLIW { shamt, rs1, rd }), //slliw
+                (0b101, 0b0000000) => Ok(Operation::SRLIW { shamt, rs1, rd }), //srliw
+                (0b101, 0b0100000) => Ok(Operation::SRAIW { shamt, rs1, rd }), //sraiw
+                (0b001 | 0b101, _) => Err(ParseError::InvalidFunct7 {
+                    word: instruction,
+                    funct7,
+                }),
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
+            }
+        }
+        0b0111011 if config.xlen == Xlen::Rv64 => {
+            //OP_32
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = ((instruction & (0b11111 << 20)) >> 20)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            match (funct3, funct7) {
+                (0b000, 0b0000000) => Ok(Operation::ADDW { rs2, rs1, rd }), //addw
+                (0b000, 0b0100000) => Ok(Operation::SUBW { rs2, rs1, rd }), //subw
+                (0b001, 0b0000000) => Ok(Operation::SLLW { rs2, rs1, rd }), //sllw
+                (0b101, 0b0000000) => Ok(Operation::SRLW { rs2, rs1, rd }), //srlw
+                (0b101, 0b0100000) => Ok(Operation::SRAW { rs2, rs1, rd }), //sraw
+                (0b100, 0b0000100) if config.zbb && rs2 == Register::ZERO => {
+                    Ok(Operation::ZEXT_H { rs1, rd }) //zext.h
+                }
+                (0b000 | 0b001 | 0b101, _) => Err(ParseError::InvalidFunct7 {
+                    word: instruction,
+                    funct7,
+                }),
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
             }
         }
         0b0110111 => {
             //LUI
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let imm = imm_big;
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = (instruction & 0xFFFFF000) as i32;
             Ok(Operation::LUI { rd, imm })
         }
         0b0010111 => {
             //AUIPC
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let imm = imm_big;
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = (instruction & 0xFFFFF000) as i32;
             Ok(Operation::AUIPC { rd, imm })
         }
         0b1101111 => {
             //JAL
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let imm = imm_big_shuffled;
-            Ok(Operation::JAL {
-                rd,
-                imm: imm as u32,
-            })
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_j_imm(instruction);
+            Ok(Operation::JAL { rd, imm })
         }
         0b1100111 => {
             //JALR
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8).try_into()?;
-            let jalr_imm = imm;
-            Ok(Operation::JALR {
-                rd,
-                rs1,
-                imm: jalr_imm as u32,
-            })
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_i_imm(instruction);
+            Ok(Operation::JALR { rd, rs1, imm })
         }
         0b1100011 => {
             //BRANCH
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8)
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
                 .try_into()
-                .unwrap();
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8)
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = ((instruction & (0b11111 << 20)) >> 20)
                 .try_into()
-                .unwrap();
-            let rs2 = (((instruction & (0b11111 << 20)) >> 20) as u8)
-                .try_into()
-                .unwrap();
-            let imm = sign_extend32(
-                ((instruction & (0b1 << 31)) >> 19)
-                    | ((instruction & (0b111111 << 25)) >> 20)
-                    | ((instruction & (0b1111 << 8)) >> 7)
-                    | ((instruction & (0b1 << 7)) << 4),
-                13,
-            );
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_b_imm(instruction);
             match funct3 {
                 0b000 => {
                     //beq
-                    Ok(Operation::BEQ {
-                        imm: imm as u32,
-                        rs1,
-                        rs2,
-                    })
+                    Ok(Operation::BEQ { imm, rs1, rs2 })
                 } //beq
-                0b001 => Ok(Operation::BNE {
-                    imm: imm as u32,
-                    rs1,
-                    rs2,
-                }), //bne
-                0b100 => Ok(Operation::BLT {
-                    imm: imm as u32,
-                    rs1,
-                    rs2,
-                }), //blt
-                0b101 => Ok(Operation::BGE {
-                    imm: imm as u32,
-                    rs1,
-                    rs2,
-                }), //bge
-                0b110 => Ok(Operation::BLTU {
-                    imm: imm as u32,
-                    rs1,
-                    rs2,
-                }), //bltu
-                0b111 => Ok(Operation::BGEU {
-                    imm: imm as u32,
-                    rs1,
-                    rs2,
-                }), //bgeu
-                0b011 => Ok(Operation::JALR {
-                    rd,
-                    rs1,
-                    imm: imm as u32,
-                }), //jalr
-                0b010 => Ok(Operation::JAL {
-                    rd,
-                    imm: imm as u32,
+                0b001 => Ok(Operation::BNE { imm, rs1, rs2 }), //bne
+                0b100 => Ok(Operation::BLT { imm, rs1, rs2 }), //blt
+                0b101 => Ok(Operation::BGE { imm, rs1, rs2 }), //bge
+                0b110 => Ok(Operation::BLTU { imm, rs1, rs2 }), //bltu
+                0b111 => Ok(Operation::BGEU { imm, rs1, rs2 }), //bgeu
+                //0b010 and 0b011 are reserved
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
                 }),
-                _ => {
-                    unreachable!()
-                }
             }
             /* let branch_imm = (((instruction & (0b1 << 31)) >> 19)
             | ((instruction & (0b111111 << 25)) >> 20)
@@ -308,14 +716,13 @@ fn parse_32bit_operation(instruction: u32) -> Result<Operation, String> {
             //let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into().unwrap();
             //let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8).try_into().unwrap();
 
-            let imm = imm as u16; //immediate
-                                  //todo!();
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8)
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
                 .try_into()
-                .unwrap();
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8)
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
                 .try_into()
-                .unwrap();
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_i_imm(instruction);
 
             match funct3 {
                 0b000 => Ok(Operation::LB { imm, rs1, rd }),  //lb
@@ -323,41 +730,300 @@ fn parse_32bit_operation(instruction: u32) -> Result<Operation, String> {
                 0b010 => Ok(Operation::LW { imm, rs1, rd }),  //lw
                 0b100 => Ok(Operation::LBU { imm, rs1, rd }), //lbu
                 0b101 => Ok(Operation::LHU { imm, rs1, rd }), //lhu
-                _ => {
-                    panic!("Unsupported funct3 {:b}", funct3)
-                }
+                0b110 if config.xlen == Xlen::Rv64 => Ok(Operation::LWU { imm, rs1, rd }), //lwu
+                0b011 if config.xlen == Xlen::Rv64 => Ok(Operation::LD { imm, rs1, rd }), //ld
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
             }
         }
         0b0100011 => {
             //STORE
 
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8)
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
                 .try_into()
-                .unwrap();
-            let rs2 = (((instruction & (0b11111 << 20)) >> 20) as u8)
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = ((instruction & (0b11111 << 20)) >> 20)
                 .try_into()
-                .unwrap();
-
-            let imm = imm_store as u16; //immediate store type
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_s_imm(instruction);
             match funct3 {
                 //size
                 0b000 => Ok(Operation::SB { imm, rs2, rs1 }),
                 0b001 => Ok(Operation::SH { imm, rs2, rs1 }),
                 0b010 => Ok(Operation::SW { imm, rs2, rs1 }),
-                _ => panic!("Unsupported funct3 {:b}", funct3),
+                0b011 if config.xlen == Xlen::Rv64 => Ok(Operation::SD { imm, rs2, rs1 }),
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
+            }
+        }
+        #[cfg(feature = "ext-f")]
+        0b0000111 if config.f => {
+            //LOAD-FP
+            let rd = FloatRegister::try_from((instruction & (0b11111 << 7)) >> 7)
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_i_imm(instruction);
+            match funct3 {
+                0b010 => Ok(Operation::FLW { imm, rs1, rd }), //flw
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
+            }
+        }
+        #[cfg(feature = "ext-f")]
+        0b0100111 if config.f => {
+            //STORE-FP
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = FloatRegister::try_from((instruction & (0b11111 << 20)) >> 20)
+                .map_err(ParseError::invalid_register)?;
+            let imm = decode_s_imm(instruction);
+            match funct3 {
+                0b010 => Ok(Operation::FSW { imm, rs2, rs1 }), //fsw
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
+            }
+        }
+        #[cfg(feature = "ext-f")]
+        0b1010011 if config.f => {
+            //OP-FP
+            let rd = FloatRegister::try_from((instruction & (0b11111 << 7)) >> 7)
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = FloatRegister::try_from((instruction & (0b11111 << 15)) >> 15)
+                .map_err(ParseError::invalid_register)?;
+            let rs2_field = (instruction & (0b11111 << 20)) >> 20;
+            let rs2 = FloatRegister::try_from(rs2_field).map_err(ParseError::invalid_register)?;
+            let rm =
+                RoundingMode::try_from(funct3 as u8).map_err(|_| ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                })?;
+            match funct7 {
+                0b0000000 => Ok(Operation::FADD_S { rs2, rs1, rd, rm }), //fadd.s
+                0b0000100 => Ok(Operation::FSUB_S { rs2, rs1, rd, rm }), //fsub.s
+                0b0001000 => Ok(Operation::FMUL_S { rs2, rs1, rd, rm }), //fmul.s
+                0b0001100 => Ok(Operation::FDIV_S { rs2, rs1, rd, rm }), //fdiv.s
+                0b0101100 if rs2_field == 0 => Ok(Operation::FSQRT_S { rs1, rd, rm }), //fsqrt.s
+                _ => Err(ParseError::InvalidFunct7 {
+                    word: instruction,
+                    funct7,
+                }),
+            }
+        }
+        #[cfg(feature = "ext-f")]
+        0b1000011 | 0b1000111 | 0b1001011 | 0b1001111 if config.f => {
+            //MADD MSUB NMSUB NMADD                      //rs3 [31:27] fmt [26:25]
+            let rd = FloatRegister::try_from((instruction & (0b11111 << 7)) >> 7)
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = FloatRegister::try_from((instruction & (0b11111 << 15)) >> 15)
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = FloatRegister::try_from((instruction & (0b11111 << 20)) >> 20)
+                .map_err(ParseError::invalid_register)?;
+            let rs3 =
+                FloatRegister::try_from(instruction >> 27).map_err(ParseError::invalid_register)?;
+            let rm =
+                RoundingMode::try_from(funct3 as u8).map_err(|_| ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                })?;
+            if funct7 & 0b11 != 0b00 {
+                //only single precision is supported, the format is in the low bits of funct7
+                return Err(ParseError::InvalidFunct7 {
+                    word: instruction,
+                    funct7,
+                });
+            }
+            match opcode {
+                0b1000011 => Ok(Operation::FMADD_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                }), //fmadd.s
+                0b1000111 => Ok(Operation::FMSUB_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                }), //fmsub.s
+                0b1001011 => Ok(Operation::FNMSUB_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                }), //fnmsub.s
+                _ => Ok(Operation::FNMADD_S {
+                    rs3,
+                    rs2,
+                    rs1,
+                    rd,
+                    rm,
+                }), //fnmadd.s
+            }
+        }
+        #[cfg(feature = "ext-a")]
+        0b0101111 => {
+            //AMO
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs2 = ((instruction & (0b11111 << 20)) >> 20)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let funct5 = (instruction & (0b11111 << 27)) >> 27;
+            let aq = instruction & (0b1 << 26) != 0;
+            let rl = instruction & (0b1 << 25) != 0;
+            if funct3 != 0b010 {
+                //only the word width exists on RV32
+                return Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                });
+            }
+            match funct5 {
+                //lr.w, rs2 has to be zero
+                0b00010 if rs2 == Register::ZERO => Ok(Operation::LR_W { rs1, rd, aq, rl }),
+                0b00010 => Err(ParseError::Reserved { word: instruction }),
+                0b00011 => Ok(Operation::SC_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //sc.w
+                0b00001 => Ok(Operation::AMOSWAP_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amoswap.w
+                0b00000 => Ok(Operation::AMOADD_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amoadd.w
+                0b00100 => Ok(Operation::AMOXOR_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amoxor.w
+                0b01100 => Ok(Operation::AMOAND_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amoand.w
+                0b01000 => Ok(Operation::AMOOR_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amoor.w
+                0b10000 => Ok(Operation::AMOMIN_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amomin.w
+                0b10100 => Ok(Operation::AMOMAX_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amomax.w
+                0b11000 => Ok(Operation::AMOMINU_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amominu.w
+                0b11100 => Ok(Operation::AMOMAXU_W {
+                    rs2,
+                    rs1,
+                    rd,
+                    aq,
+                    rl,
+                }), //amomaxu.w
+                _ => Err(ParseError::InvalidFunct5 {
+                    word: instruction,
+                    funct5,
+                }),
+            }
+        }
+        0b0001111 => {
+            //MISC-MEM
+            match funct3 {
+                0b000 if instruction == 0x0100000f => Ok(Operation::PAUSE {}), //pause
+                0b000 => Ok(Operation::FENCE {
+                    fm: (instruction >> 28) as u8,
+                    pred: ((instruction >> 24) & 0b1111) as u8,
+                    succ: ((instruction >> 20) & 0b1111) as u8,
+                }), //fence
+                0b001 => Ok(Operation::FENCE_I {}),                            //fence.i
+                _ => Err(ParseError::InvalidFunct3 {
+                    word: instruction,
+                    funct3,
+                }),
             }
         }
         0b1110011 => {
             //SYSTEM
-            let rd = (((instruction & (0b11111 << 7)) >> 7) as u8).try_into()?;
-            let rs1 = (((instruction & (0b11111 << 15)) >> 15) as u8).try_into()?;
-            let csr = imm as u16; //imm
+            let rd = ((instruction & (0b11111 << 7)) >> 7)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let rs1 = ((instruction & (0b11111 << 15)) >> 15)
+                .try_into()
+                .map_err(ParseError::invalid_register)?;
+            let csr = (instruction >> 20) as u16;
             if instruction == 807403635
             //mret, basically magic number
             {
                 Ok(Operation::MRET {})
+            } else if instruction == 0x00000073 {
+                //ecall
+                Ok(Operation::ECALL {})
+            } else if instruction == 0x00100073 {
+                //ebreak
+                Ok(Operation::EBREAK {})
             } else {
                 match funct3 {
+                    //sfence.vma is told apart from the funct12 based instructions by funct7
+                    0b000 if funct7 == 0b0001001 && rd == Register::ZERO => {
+                        let rs2 = ((instruction & (0b11111 << 20)) >> 20)
+                            .try_into()
+                            .map_err(ParseError::invalid_register)?;
+                        Ok(Operation::SFENCE_VMA { rs1, rs2 })
+                    }
+                    0b000 => Err(ParseError::InvalidFunct12 {
+                        word: instruction,
+                        funct12: instruction >> 20,
+                    }),
                     0b001 => {
                         //CSRRW
                         Ok(Operation::CSRRW { csr, rs1, rd })
@@ -404,49 +1070,1228 @@ fn parse_32bit_operation(instruction: u32) -> Result<Operation, String> {
                         })
                         //zimm
                     }
-                    _ => panic!("Unsupported funct3 {:b}", funct3),
+                    _ => Err(ParseError::InvalidFunct3 {
+                        word: instruction,
+                        funct3,
+                    }),
                 }
             }
         }
-        _ => Err(format!("Invalid opcode! {:b}", opcode.clone())),
+        _ => Err(ParseError::InvalidOpcode {
+            word: instruction,
+            opcode,
+        }),
     }
 }
 
-/*trait SignExtend {
-    fn sign_extend(&self, valid_bits: usize) -> u32;
-}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[cfg(feature = "ext-a")]
+    #[test]
+    fn atomics() {
+        assert!(matches!(
+            parse_32bit_operation(0x1005a52f),
+            Ok(Operation::LR_W {
+                rs1: Register::A1,
+                rd: Register::A0,
+                aq: false,
+                rl: false,
+            })
+        ));
+        assert!(matches!(
+            parse_32bit_operation(0x1ec5a52f),
+            Ok(Operation::SC_W {
+                rs2: Register::A2,
+                rs1: Register::A1,
+                rd: Register::A0,
+                aq: true,
+                rl: true,
+            })
+        ));
+        assert!(matches!(
+            parse_32bit_operation(0x00c5a52f),
+            Ok(Operation::AMOADD_W {
+                rs2: Register::A2,
+                rs1: Register::A1,
+                rd: Register::A0,
+                aq: false,
+                rl: false,
+            })
+        ));
+        assert!(matches!(
+            parse_32bit_operation(0xe2c5a52f),
+            Ok(Operation::AMOMAXU_W {
+                aq: false,
+                rl: true,
+                ..
+            })
+        ));
+        // lr.w a0, (a1) with rs2 = a2
+        assert_eq!(
+            parse_32bit_operation(0x10c5a52f),
+            Err(ParseError::Reserved { word: 0x10c5a52f })
+        );
+        // funct5 0b00101 is not an AMO
+        assert!(parse_32bit_operation(0x28c5a52f).is_err());
+        // amoadd.d does not exist on RV32
+        assert!(parse_32bit_operation(0x00c5b52f).is_err());
+    }
 
-impl SignExtend for u16 {
-    fn sign_extend(&self, valid_bits: usize) -> u32 {
-        let shift = 16 - valid_bits;
-        ((((self << shift) as i16) >> shift) as i32) as u32
+    #[cfg(feature = "ext-a")]
+    #[test]
+    fn encode_round_trip() {
+        let words = [
+            0x00b50533, // add a0, a0, a1
+            0x40b50533, // sub a0, a0, a1
+            0x00b51533, // sll a0, a0, a1
+            0x00b52533, // slt a0, a0, a1
+            0x00b53533, // sltu a0, a0, a1
+            0x00b54533, // xor a0, a0, a1
+            0x00b55533, // srl a0, a0, a1
+            0x40b55533, // sra a0, a0, a1
+            0x00b56533, // or a0, a0, a1
+            0x00b57533, // and a0, a0, a1
+            0xfff50513, // addi a0, a0, -1
+            0xffb52513, // slti a0, a0, -5
+            0x00153513, // sltiu a0, a0, 1
+            0xfff54513, // xori a0, a0, -1
+            0x0ff56513, // ori a0, a0, 255
+            0x0ff57513, // andi a0, a0, 255
+            0x00351513, // slli a0, a0, 3
+            0x00355513, // srli a0, a0, 3
+            0x40355513, // srai a0, a0, 3
+            0x12345537, // lui a0, 0x12345
+            0x12345517, // auipc a0, 0x12345
+            0x010000ef, // jal ra, 16
+            0xff9ff06f, // jal zero, -8
+            0xff8500e7, // jalr ra, -8(a0)
+            0x00b50863, // beq a0, a1, 16
+            0xfeb51ce3, // bne a0, a1, -8
+            0x00b54863, // blt a0, a1, 16
+            0x00b55863, // bge a0, a1, 16
+            0x00b56863, // bltu a0, a1, 16
+            0x00b57863, // bgeu a0, a1, 16
+            0xffc10503, // lb a0, -4(sp)
+            0xffc11503, // lh a0, -4(sp)
+            0x00812503, // lw a0, 8(sp)
+            0xffc14503, // lbu a0, -4(sp)
+            0xffc15503, // lhu a0, -4(sp)
+            0xfe110e23, // sb ra, -4(sp)
+            0xfe111e23, // sh ra, -4(sp)
+            0xfe112e23, // sw ra, -4(sp)
+            0x30059573, // csrrw a0, mstatus, a1
+            0x3005a573, // csrrs a0, mstatus, a1
+            0x3005b573, // csrrc a0, mstatus, a1
+            0x3002d573, // csrrwi a0, mstatus, 5
+            0x3002e573, // csrrsi a0, mstatus, 5
+            0x3002f573, // csrrci a0, mstatus, 5
+            0x30200073, // mret
+            0x1005a52f, // lr.w a0, (a1)
+            0x1ec5a52f, // sc.w.aqrl a0, a2, (a1)
+            0x08c5a52f, // amoswap.w a0, a2, (a1)
+            0x00c5a52f, // amoadd.w a0, a2, (a1)
+            0xe2c5a52f, // amomaxu.w.rl a0, a2, (a1)
+        ];
+        for word in words {
+            let operation = parse_32bit_operation(word).unwrap();
+            assert_eq!(operation.encode(), Ok(word), "{:#010x} {}", word, operation);
+        }
     }
-}
 
-impl SignExtend for u32 {
-    fn sign_extend(&self, valid_bits: usize) -> u32 {
-        let shift = 32 - valid_bits;
-        (((self << shift) as i32) >> shift) as u32
+    #[test]
+    fn trap_value() {
+        let error = parse(&[0xff, 0xff, 0xff, 0xff]).unwrap_err();
+        assert_eq!(error.trap_value(), Some(0xffffffff));
+        // add with a reserved funct7
+        let error = parse_32bit_operation(0x0ab50533).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InvalidFunct7 {
+                word: 0x0ab50533,
+                funct7: 0b101
+            }
+        );
+        assert_eq!(error.trap_value(), Some(0x0ab50533));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    fn jal_offset_is_sign_extended() {
+        // jal zero, -8
+        let operation = parse_32bit_operation(0xff9ff06f).unwrap();
+        assert_eq!(operation.jump_target(0x100), Some(0xf8));
+    }
 
     #[test]
-    fn sign_extend_u16() {
-        assert_eq!(0xffffffff, 0x1u16.sign_extend(1));
-        assert_eq!(0x1, 0x1u16.sign_extend(2));
-        assert_eq!(0xfffffff9, 0x9u16.sign_extend(4));
-        assert_eq!(0x00000009, 0x9u16.sign_extend(5));
+    fn reserved_branch_funct3() {
+        // beq a0, a1, 16 with funct3 0b011 and 0b010
+        assert_eq!(
+            parse_32bit_operation(0x00b53863).unwrap_err(),
+            ParseError::InvalidFunct3 {
+                word: 0x00b53863,
+                funct3: 0b011
+            }
+        );
+        assert!(parse_32bit_operation(0x00b52863).is_err());
+    }
+
+    #[test]
+    fn branch_offset_in_bytes() {
+        // beq a0, a1, .+8
+        let operation = parse_32bit_operation(0x00b50463).unwrap();
+        assert_eq!(operation.branch_offset_bytes(), Some(8));
+        // jal zero, .-8
+        let operation = parse_32bit_operation(0xff9ff06f).unwrap();
+        assert_eq!(operation.branch_offset_bytes(), Some(-8));
+        // jalr ra, -8(a0)
+        let operation = parse_32bit_operation(0xff8500e7).unwrap();
+        assert_eq!(operation.branch_offset_bytes(), None);
+    }
+
+    #[test]
+    fn no_branch_encoding_panics() {
+        // xorshift, so the test is reproducible
+        let mut state = 0x2545f491u32;
+        for _ in 0..100_000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let word = (state & !0b1111111) | 0b1100011;
+            let result = parse_32bit_operation(word);
+            let funct3 = (word >> 12) & 0b111;
+            assert_eq!(result.is_err(), funct3 == 0b010 || funct3 == 0b011);
+        }
     }
 
     #[test]
-    fn sign_extend_u32() {
-        assert_eq!(0xffffffff, 0x1u32.sign_extend(1));
-        assert_eq!(0x1, 0x1u32.sign_extend(2));
-        assert_eq!(0xfffffff9, 0x9u32.sign_extend(4));
-        assert_eq!(0x00000009, 0x9u32.sign_extend(5));
+    fn invalid_load_funct3() {
+        // lw a0, 8(sp) with funct3 0b011, 0b110 and 0b111
+        for word in [0x00813503, 0x00816503, 0x00817503] {
+            assert_eq!(
+                parse_32bit_operation(word).unwrap_err().trap_value(),
+                Some(word)
+            );
+        }
+    }
+    #[test]
+    fn invalid_store_funct3() {
+        // sw ra, -4(sp) with funct3 0b011 to 0b111
+        for word in [0xfe113e23, 0xfe114e23, 0xfe115e23, 0xfe116e23, 0xfe117e23] {
+            assert_eq!(
+                parse_32bit_operation(word).unwrap_err().trap_value(),
+                Some(word)
+            );
+        }
+    }
+    #[test]
+    fn carry_less_multiply() {
+        let operations = [
+            (0x0ac59533, "clmul a0, a1, a2"),
+            (0x0ac5b533, "clmulh a0, a1, a2"),
+            (0x0ac5a533, "clmulr a0, a1, a2"),
+        ];
+        for (word, text) in operations {
+            let operation = parse_32bit_operation(word).unwrap();
+            assert_eq!(operation.to_string(), text);
+            assert_eq!(operation.encode(), Ok(word));
+            assert!(parse_32bit_operation_with(
+                word,
+                &IsaConfig {
+                    zbc: false,
+                    ..IsaConfig::default()
+                }
+            )
+            .is_err());
+        }
+    }
+    #[test]
+    fn system() {
+        assert!(matches!(
+            parse_32bit_operation(0x00000073),
+            Ok(Operation::ECALL {})
+        ));
+        assert!(matches!(
+            parse_32bit_operation(0x00100073),
+            Ok(Operation::EBREAK {})
+        ));
+        assert!(matches!(
+            parse_32bit_operation(0x30200073),
+            Ok(Operation::MRET {})
+        ));
+        // funct3 0b100 is reserved
+        assert_eq!(
+            parse_32bit_operation(0x30054573).unwrap_err().trap_value(),
+            Some(0x30054573)
+        );
+        // unknown funct12
+        assert!(parse_32bit_operation(0x00200073).is_err());
+    }
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn compressed() {
+        let nop = parse(&[0x01, 0x00]).unwrap();
+        assert!(matches!(nop.width, InstructionWidth::Bit16));
+        assert!(matches!(
+            nop.operation,
+            Operation::ADDI {
+                imm: 0,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            }
+        ));
+        assert_eq!(
+            parse(&0x157du16.to_le_bytes()).unwrap().to_assembly(),
+            "c.addi a0, -1"
+        );
+        assert_eq!(
+            parse(&0x4515u16.to_le_bytes()).unwrap().to_assembly(),
+            "c.li a0, 5"
+        );
+        assert!(matches!(
+            parse_16bit_operation(0x2021),
+            Ok(Operation::JAL {
+                rd: Register::RA,
+                imm: 8,
+            })
+        ));
+        assert!(matches!(
+            parse_16bit_operation(0x3ffd),
+            Ok(Operation::JAL {
+                rd: Register::RA,
+                imm: -2,
+            })
+        ));
+        // a compressed instruction followed by a 32 bit one
+        let instruction = parse(&[0x01, 0x00, 0x13, 0x05]).unwrap();
+        assert!(matches!(instruction.width, InstructionWidth::Bit16));
+        // quadrant 0 and 2 are not supported yet
+        assert!(parse(&[0x00, 0x40]).is_err());
+        assert!(parse(&[0x02, 0x40]).is_err());
     }
-}*/
+
+    #[test]
+    fn csr_number_is_not_sign_extended() {
+        // csrr a0, cycle
+        let operation = parse_32bit_operation(0xc0002573).unwrap();
+        assert_eq!(
+            operation,
+            Operation::CSRRS {
+                csr: 0xc00,
+                rs1: Register::ZERO,
+                rd: Register::A0,
+            }
+        );
+        assert_eq!(operation.to_string(), "csrrs a0, cycle, zero");
+    }
+
+    #[test]
+    fn negative_immediates() {
+        // addi a0, a0, -1
+        assert_eq!(
+            parse_32bit_operation(0xfff50513),
+            Ok(Operation::ADDI {
+                imm: -1,
+                rs1: Register::A0,
+                rd: Register::A0,
+            })
+        );
+        // andi a0, a0, -16
+        assert_eq!(
+            parse_32bit_operation(0xff057513),
+            Ok(Operation::ANDI {
+                imm: -16,
+                rs1: Register::A0,
+                rd: Register::A0,
+            })
+        );
+        // sw ra, -4(sp)
+        assert_eq!(
+            parse_32bit_operation(0xfe112e23),
+            Ok(Operation::SW {
+                imm: -4,
+                rs2: Register::RA,
+                rs1: Register::SP,
+            })
+        );
+        // lui a0, 0x80000
+        assert_eq!(
+            parse_32bit_operation(0x80000537),
+            Ok(Operation::LUI {
+                rd: Register::A0,
+                imm: i32::MIN,
+            })
+        );
+    }
+
+    #[test]
+    fn jalr_negative_offset() {
+        // jalr ra, -8(a0)
+        let operation = parse_32bit_operation(0xff8500e7).unwrap();
+        assert_eq!(
+            operation,
+            Operation::JALR {
+                rd: Register::RA,
+                rs1: Register::A0,
+                imm: -8,
+            }
+        );
+        assert_eq!(operation.to_string(), "jalr ra, -8(a0)");
+        assert_eq!(operation.encode(), Ok(0xff8500e7));
+    }
+
+    #[test]
+    fn load_negative_offset() {
+        // lw a0, -4(sp)
+        let operation = parse_32bit_operation(0xffc12503).unwrap();
+        assert_eq!(
+            operation,
+            Operation::LW {
+                imm: -4,
+                rs1: Register::SP,
+                rd: Register::A0,
+            }
+        );
+        assert_eq!(operation.to_string(), "lw a0, -4(sp)");
+        // lbu t0, -2048(a1)
+        assert_eq!(
+            parse_32bit_operation(0x8005c283),
+            Ok(Operation::LBU {
+                imm: -2048,
+                rs1: Register::A1,
+                rd: Register::T0,
+            })
+        );
+    }
+
+    #[test]
+    fn rv64() {
+        let rv64 = IsaConfig {
+            xlen: Xlen::Rv64,
+            ..IsaConfig::default()
+        };
+        for (word, text) in [
+            (0x0015051b, "addiw a0, a0, 1"),
+            (0x41f5551b, "sraiw a0, a0, 31"),
+            (0x00b5053b, "addw a0, a0, a1"),
+            (0x40b5053b, "subw a0, a0, a1"),
+            (0x00813503, "ld a0, 8(sp)"),
+            (0x0005e503, "lwu a0, 0(a1)"),
+            (0x00113423, "sd ra, 8(sp)"),
+        ] {
+            let operation = parse_32bit_operation_with(word, &rv64).unwrap();
+            assert_eq!(operation.to_string(), text);
+            assert_eq!(operation.encode(), Ok(word), "{}", text);
+            // not part of RV32
+            assert!(parse_32bit_operation(word).is_err(), "{}", text);
+        }
+        // 6 bit shift amounts
+        assert_eq!(
+            parse_32bit_operation_with(0x02851513, &rv64),
+            Ok(Operation::SLLI {
+                shamt: 40,
+                rs1: Register::A0,
+                rd: Register::A0,
+            })
+        );
+        assert_eq!(
+            parse_32bit_operation_with(0x43f55513, &rv64),
+            Ok(Operation::SRAI {
+                shamt: 63,
+                rs1: Register::A0,
+                rd: Register::A0,
+            })
+        );
+        assert_eq!(
+            Operation::SRAI {
+                shamt: 63,
+                rs1: Register::A0,
+                rd: Register::A0,
+            }
+            .encode(),
+            Ok(0x43f55513)
+        );
+    }
+
+    #[test]
+    fn rv32_shift_amount() {
+        // slli a0, a0, 32 and srli a0, a0, 32 are only valid on RV64
+        assert_eq!(
+            parse_32bit_operation(0x02051513),
+            Err(ParseError::InvalidFunct7 {
+                word: 0x02051513,
+                funct7: 0b0000001,
+            })
+        );
+        assert!(parse_32bit_operation(0x02055513).is_err());
+        assert!(parse_32bit_operation(0x42055513).is_err());
+        // slli with a nonzero funct7
+        assert!(parse_32bit_operation(0x40151513).is_err());
+        // slli a0, a0, 31
+        assert_eq!(
+            parse_32bit_operation(0x01f51513),
+            Ok(Operation::SLLI {
+                shamt: 31,
+                rs1: Register::A0,
+                rd: Register::A0,
+            })
+        );
+    }
+
+    #[test]
+    fn all_zero_is_illegal() {
+        assert_eq!(
+            parse(&[0x00, 0x00, 0x00, 0x00]).unwrap_err(),
+            ParseError::Illegal
+        );
+        assert_eq!(parse(&[0x00, 0x00]).unwrap_err(), ParseError::Illegal);
+        assert_eq!(parse_32bit_operation(0), Err(ParseError::Illegal));
+        assert_eq!(ParseError::Illegal.trap_value(), Some(0));
+        // other words with a zero opcode are just unknown
+        assert!(matches!(
+            parse_32bit_operation(0x00000080),
+            Err(ParseError::InvalidOpcode { .. })
+        ));
+    }
+
+    #[test]
+    fn lenient() {
+        let unknown = [0x7f, 0x70, 0x00, 0x00];
+        assert!(parse(&unknown).is_err());
+        let instruction = parse_lenient(&unknown);
+        assert!(instruction.is_32bit());
+        assert_eq!(
+            instruction.operation,
+            Operation::Unknown { raw: 0x0000707f }
+        );
+        assert_eq!(instruction.to_assembly(), "unknown 0x707f");
+        // compressed quadrant 0 is not supported yet
+        let instruction = parse_lenient(&[0x00, 0x40]);
+        assert!(instruction.is_16bit());
+        assert_eq!(instruction.operation, Operation::Unknown { raw: 0x4000 });
+        assert_eq!(instruction.to_assembly(), "unknown 0x4000");
+        // short input keeps the bytes that are there
+        let empty = parse_lenient(&[]);
+        assert!(empty.is_16bit());
+        assert_eq!(empty.operation, Operation::Unknown { raw: 0 });
+        let byte = parse_lenient(&[0x13]);
+        assert!(byte.is_32bit());
+        assert_eq!(byte.operation, Operation::Unknown { raw: 0x13 });
+        let half = parse_lenient(&[0x13, 0x05]);
+        assert!(half.is_32bit());
+        assert_eq!(half.operation, Operation::Unknown { raw: 0x0513 });
+        // known encodings decode as usual
+        assert_eq!(
+            parse_lenient(&[0x13, 0x05, 0xa0, 0x00]).operation,
+            Operation::ADDI {
+                imm: 10,
+                rs1: Register::ZERO,
+                rd: Register::A0,
+            }
+        );
+    }
+
+    #[test]
+    fn address_generation() {
+        for (word, operation) in [
+            (
+                0x20c5a533,
+                Operation::SH1ADD {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x20c5c533,
+                Operation::SH2ADD {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x20c5e533,
+                Operation::SH3ADD {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+        ] {
+            assert_eq!(parse_32bit_operation(word), Ok(operation));
+            let without_zba = IsaConfig {
+                zba: false,
+                ..IsaConfig::default()
+            };
+            assert!(parse_32bit_operation_with(word, &without_zba).is_err());
+        }
+        let sh2add = parse_32bit_operation(0x20c5c533).unwrap();
+        assert_eq!(sh2add.to_string(), "sh2add a0, a1, a2");
+        assert_eq!(sh2add.folds_to_constant(Some(3), Some(100)), Some(112));
+    }
+
+    #[test]
+    fn basic_bit_manipulation() {
+        for (word, operation) in [
+            (
+                0x40c5f533,
+                Operation::ANDN {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x0ac5c533,
+                Operation::MIN {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x60059513,
+                Operation::CLZ {
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x6985d513,
+                Operation::REV8 {
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x2875d513,
+                Operation::ORC_B {
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x6075d513,
+                Operation::RORI {
+                    shamt: 7,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x0805c533,
+                Operation::ZEXT_H {
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+        ] {
+            assert_eq!(parse_32bit_operation(word), Ok(operation));
+            let without_zbb = IsaConfig {
+                zbb: false,
+                ..IsaConfig::default()
+            };
+            assert!(parse_32bit_operation_with(word, &without_zbb).is_err());
+        }
+        // rev8 and zext.h are encoded differently on RV64
+        let rv64 = IsaConfig {
+            xlen: Xlen::Rv64,
+            ..IsaConfig::default()
+        };
+        assert!(parse_32bit_operation_with(0x6985d513, &rv64).is_err());
+        assert_eq!(
+            parse_32bit_operation_with(0x6b85d513, &rv64),
+            Ok(Operation::REV8 {
+                rs1: Register::A1,
+                rd: Register::A0,
+            })
+        );
+        assert_eq!(
+            parse_32bit_operation_with(0x0805c53b, &rv64),
+            Ok(Operation::ZEXT_H {
+                rs1: Register::A1,
+                rd: Register::A0,
+            })
+        );
+        assert_eq!(
+            parse_32bit_operation(0x60359513),
+            Err(ParseError::InvalidFunct12 {
+                word: 0x60359513,
+                funct12: 0x603
+            })
+        );
+    }
+
+    #[test]
+    fn single_bit() {
+        for (word, operation) in [
+            (
+                0x48c59533,
+                Operation::BCLR {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x48c5d533,
+                Operation::BEXT {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x68c59533,
+                Operation::BINV {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x28c59533,
+                Operation::BSET {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x48759513,
+                Operation::BCLRI {
+                    shamt: 7,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x4875d513,
+                Operation::BEXTI {
+                    shamt: 7,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x68759513,
+                Operation::BINVI {
+                    shamt: 7,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x28759513,
+                Operation::BSETI {
+                    shamt: 7,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+        ] {
+            assert_eq!(parse_32bit_operation(word), Ok(operation));
+            let without_zbs = IsaConfig {
+                zbs: false,
+                ..IsaConfig::default()
+            };
+            assert!(parse_32bit_operation_with(word, &without_zbs).is_err());
+        }
+        let bexti = parse_32bit_operation(0x4875d513).unwrap();
+        assert_eq!(bexti.to_string(), "bexti a0, a1, 7");
+        assert_eq!(bexti.folds_to_constant(Some(0x80), None), Some(1));
+    }
+
+    #[test]
+    fn conditional_zero() {
+        for (word, text, operation) in [
+            (
+                0x0ec5d533,
+                "czero.eqz a0, a1, a2",
+                Operation::CZERO_EQZ {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x0ec5f533,
+                "czero.nez a0, a1, a2",
+                Operation::CZERO_NEZ {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                },
+            ),
+        ] {
+            assert_eq!(parse_32bit_operation(word), Ok(operation));
+            assert_eq!(operation.to_string(), text);
+            let without_zicond = IsaConfig {
+                zicond: false,
+                ..IsaConfig::default()
+            };
+            assert!(parse_32bit_operation_with(word, &without_zicond).is_err());
+        }
+    }
+
+    #[cfg(feature = "ext-f")]
+    #[test]
+    fn rounding_modes() {
+        // fadd.s fa0, fa1, fa2 with every rounding mode in funct3
+        for (funct3, rm, text) in [
+            (0b000, RoundingMode::Rne, "fadd.s fa0, fa1, fa2, rne"),
+            (0b001, RoundingMode::Rtz, "fadd.s fa0, fa1, fa2, rtz"),
+            (0b010, RoundingMode::Rdn, "fadd.s fa0, fa1, fa2, rdn"),
+            (0b011, RoundingMode::Rup, "fadd.s fa0, fa1, fa2, rup"),
+            (0b100, RoundingMode::Rmm, "fadd.s fa0, fa1, fa2, rmm"),
+            (0b111, RoundingMode::Dyn, "fadd.s fa0, fa1, fa2"),
+        ] {
+            let word = 0x00c58553 | (funct3 << 12);
+            let operation = parse_32bit_operation(word).unwrap();
+            assert_eq!(
+                operation,
+                Operation::FADD_S {
+                    rs2: FloatRegister::F12,
+                    rs1: FloatRegister::F11,
+                    rd: FloatRegister::F10,
+                    rm,
+                }
+            );
+            assert_eq!(operation.to_string(), text);
+            assert_eq!(operation.encode(), Ok(word));
+        }
+        for funct3 in [0b101, 0b110] {
+            let word = 0x00c58553 | (funct3 << 12);
+            assert_eq!(
+                parse_32bit_operation(word),
+                Err(ParseError::InvalidFunct3 { word, funct3 })
+            );
+        }
+        assert_eq!(
+            parse_32bit_operation(0x5805f553).unwrap().to_string(),
+            "fsqrt.s fa0, fa1"
+        );
+        let without_f = IsaConfig {
+            f: false,
+            ..IsaConfig::default()
+        };
+        assert!(parse_32bit_operation_with(0x00c5f553, &without_f).is_err());
+    }
+
+    #[cfg(feature = "ext-f")]
+    #[test]
+    fn fused_multiply_add() {
+        let operations = [
+            (0x68c5f543, "fmadd.s fa0, fa1, fa2, fa3"),
+            (0x68c5f547, "fmsub.s fa0, fa1, fa2, fa3"),
+            (0x68c5f54b, "fnmsub.s fa0, fa1, fa2, fa3"),
+            (0x68c5f54f, "fnmadd.s fa0, fa1, fa2, fa3"),
+        ];
+        for (word, text) in operations {
+            let operation = parse_32bit_operation(word).unwrap();
+            assert_eq!(operation.to_string(), text);
+            assert_eq!(operation.encode(), Ok(word));
+        }
+        // fmadd.s ft11, ft10, ft9, ft8, rtz
+        assert_eq!(
+            parse_32bit_operation(0xe1df1fc3),
+            Ok(Operation::FMADD_S {
+                rs3: FloatRegister::F28,
+                rs2: FloatRegister::F29,
+                rs1: FloatRegister::F30,
+                rd: FloatRegister::F31,
+                rm: RoundingMode::Rtz,
+            })
+        );
+        // fmadd.d fa0, fa1, fa2, fa3
+        assert!(matches!(
+            parse_32bit_operation(0x6ac5f543),
+            Err(ParseError::InvalidFunct7 { .. })
+        ));
+    }
+
+    #[test]
+    fn fence_and_pause() {
+        assert_eq!(parse_32bit_operation(0x0100000f), Ok(Operation::PAUSE {}));
+        assert_eq!(Operation::PAUSE {}.to_string(), "pause");
+        for (word, fm, pred, succ, assembly) in [
+            (0x0ff0000f, 0, 0b1111, 0b1111, "fence iorw, iorw"),
+            // fence w, w is an ordinary fence
+            (0x0110000f, 0, 0b0001, 0b0001, "fence w, w"),
+            (0x0a60000f, 0, 0b1010, 0b0110, "fence ir, or"),
+            (0x8330000f, 0b1000, 0b0011, 0b0011, "fence.tso"),
+        ] {
+            let fence = parse_32bit_operation(word).unwrap();
+            assert_eq!(fence, Operation::FENCE { fm, pred, succ });
+            assert_eq!(fence.to_string(), assembly);
+            assert_eq!(fence.encode(), Ok(word));
+        }
+        assert_eq!(parse_32bit_operation(0x0000100f), Ok(Operation::FENCE_I {}));
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn big_endian() {
+        // addi a0, a1, -4
+        let little = parse(&[0x13, 0x85, 0xc5, 0xff]).unwrap();
+        let big = parse_be(&[0xff, 0xc5, 0x85, 0x13]).unwrap();
+        assert!(big.is_32bit());
+        assert_eq!(big.operation, little.operation);
+        assert_eq!(
+            big.operation,
+            Operation::ADDI {
+                imm: -4,
+                rs1: Register::A1,
+                rd: Register::A0,
+            }
+        );
+        // c.addi a0, 1 in the low halfword of the word
+        let little = parse(&[0x05, 0x05]).unwrap();
+        let big = parse_be(&[0x00, 0x00, 0x05, 0x05]).unwrap();
+        assert!(big.is_16bit());
+        assert_eq!(big.operation, little.operation);
+        assert_eq!(big.raw, 0x0505);
+        // the high halfword is not part of the compressed instruction
+        let big = parse_be_with_config(&[0x12, 0x34, 0x05, 0x05], &IsaConfig::default()).unwrap();
+        assert!(big.is_16bit());
+        assert_eq!(big.operation, little.operation);
+        assert_eq!(big.raw, 0x0505);
+    }
+
+    #[test]
+    fn resolved_targets() {
+        // bne a0, a1, -8
+        let (instruction, target) = parse_with_pc(&[0xe3, 0x1c, 0xb5, 0xfe], 0x1000).unwrap();
+        assert!(instruction.operation.is_branch());
+        assert_eq!(target, Some(0xff8));
+        // jal ra, 16
+        let (_, target) = parse_with_pc(&[0xef, 0x00, 0x00, 0x01], 0x1000).unwrap();
+        assert_eq!(target, Some(0x1010));
+        // addi a0, zero, 10
+        let (_, target) = parse_with_pc(&[0x13, 0x05, 0xa0, 0x00], 0x1000).unwrap();
+        assert_eq!(target, None);
+        // jalr ra, -8(a0)
+        let (_, target) = parse_with_pc(&[0xe7, 0x00, 0x85, 0xff], 0x1000).unwrap();
+        assert_eq!(target, None);
+    }
+
+    #[cfg(feature = "ext-m")]
+    #[test]
+    fn multiply_divide() {
+        let operations = [
+            (0x02c58533, "mul a0, a1, a2"),
+            (0x02c59533, "mulh a0, a1, a2"),
+            (0x02c5a533, "mulhsu a0, a1, a2"),
+            (0x02c5b533, "mulhu a0, a1, a2"),
+            (0x02c5c533, "div a0, a1, a2"),
+            (0x02c5d533, "divu a0, a1, a2"),
+            (0x02c5e533, "rem a0, a1, a2"),
+            (0x02c5f533, "remu a0, a1, a2"),
+        ];
+        for (word, text) in operations {
+            let operation = parse_32bit_operation(word).unwrap();
+            assert_eq!(operation.to_string(), text);
+            assert_eq!(operation.encode(), Ok(word));
+        }
+    }
+
+    #[cfg(all(feature = "ext-m", feature = "ext-c"))]
+    #[test]
+    fn instruction_set() {
+        // mul a0, a1, a2
+        let mul = [0x33, 0x85, 0xc5, 0x02];
+        assert_eq!(
+            parse_with_isa(&mul, InstructionSet::I).unwrap_err(),
+            ParseError::DisabledExtension { word: 0x02c58533 }
+        );
+        let rv32im = InstructionSet::I | InstructionSet::M;
+        assert!(parse_with_isa(&mul, rv32im).is_ok());
+        // c.addi a0, 1
+        let c_addi = [0x05, 0x05];
+        assert_eq!(
+            parse_with_isa(&c_addi, rv32im).unwrap_err(),
+            ParseError::DisabledExtension { word: 0x0505 }
+        );
+        assert!(parse_with_isa(&c_addi, rv32im | InstructionSet::C).is_ok());
+        // add a1, a0, a0
+        assert!(parse_with_isa(&[0xb3, 0x05, 0xa5, 0x00], InstructionSet::I).is_ok());
+    }
+
+    #[test]
+    fn reserved_funct7() {
+        // add a0, a1, a2 with every funct3 and a funct7 that no extension uses
+        for funct3 in 0..8 {
+            for funct7 in [0b0000010, 0b1000000, 0b0100001, 0b1111111] {
+                let word = 0x00c58533 | (funct3 << 12) | (funct7 << 25);
+                assert_eq!(
+                    parse_32bit_operation(word),
+                    Err(ParseError::InvalidFunct7 { word, funct7 })
+                );
+            }
+        }
+        // without the bit manipulation extensions only sub and sra use funct7 0b0100000
+        let base = IsaConfig {
+            zbc: false,
+            zba: false,
+            zbb: false,
+            zbs: false,
+            zicond: false,
+            ..IsaConfig::default()
+        };
+        for funct3 in [0b001, 0b010, 0b011, 0b100, 0b110, 0b111] {
+            let word = 0x40c58533 | (funct3 << 12);
+            assert!(matches!(
+                parse_32bit_operation_with(word, &base),
+                Err(ParseError::InvalidFunct7 {
+                    funct7: 0b0100000,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn compressed_quadrant_2() {
+        let ebreak = parse(&0x9002u16.to_le_bytes()).unwrap();
+        assert!(ebreak.is_16bit());
+        assert_eq!(ebreak.operation, Operation::EBREAK {});
+        for (halfword, operation) in [
+            (
+                0x8082,
+                Operation::JALR {
+                    rd: Register::ZERO,
+                    rs1: Register::RA,
+                    imm: 0,
+                },
+            ),
+            (
+                0x9502,
+                Operation::JALR {
+                    rd: Register::RA,
+                    rs1: Register::A0,
+                    imm: 0,
+                },
+            ),
+            (
+                0x852e,
+                Operation::ADD {
+                    rs2: Register::A1,
+                    rs1: Register::ZERO,
+                    rd: Register::A0,
+                },
+            ),
+            (
+                0x952e,
+                Operation::ADD {
+                    rs2: Register::A1,
+                    rs1: Register::A0,
+                    rd: Register::A0,
+                },
+            ),
+        ] {
+            assert_eq!(parse_16bit_operation(halfword), Ok(operation));
+        }
+        // c.jr with rs1 zero
+        assert_eq!(
+            parse_16bit_operation(0x8002),
+            Err(ParseError::Reserved { word: 0x8002 })
+        );
+    }
+
+    #[test]
+    fn sfence_vma() {
+        assert_eq!(
+            parse_32bit_operation(0x12b50073),
+            Ok(Operation::SFENCE_VMA {
+                rs1: Register::A0,
+                rs2: Register::A1,
+            })
+        );
+        // sfence.vma zero, zero flushes everything
+        let all = parse_32bit_operation(0x12000073).unwrap();
+        assert_eq!(all.to_string(), "sfence.vma zero, zero");
+        assert!(all.is_system());
+        // a nonzero rd is not sfence.vma
+        assert!(parse_32bit_operation(0x12b50573).is_err());
+    }
+
+    #[cfg(feature = "ext-a")]
+    #[test]
+    fn atomic_ordering() {
+        for (word, aq, rl, text) in [
+            (0x00c5a52f, false, false, "amoadd.w a0, a2, (a1)"),
+            (0x04c5a52f, true, false, "amoadd.w.aq a0, a2, (a1)"),
+            (0x02c5a52f, false, true, "amoadd.w.rl a0, a2, (a1)"),
+            (0x06c5a52f, true, true, "amoadd.w.aqrl a0, a2, (a1)"),
+        ] {
+            let amoadd = parse_32bit_operation(word).unwrap();
+            assert_eq!(
+                amoadd,
+                Operation::AMOADD_W {
+                    rs2: Register::A2,
+                    rs1: Register::A1,
+                    rd: Register::A0,
+                    aq,
+                    rl,
+                }
+            );
+            assert_eq!(amoadd.to_string(), text);
+            assert_eq!(amoadd.encode(), Ok(word));
+        }
+        // lr.w.aq a0, (a1)
+        assert_eq!(
+            parse_32bit_operation(0x1405a52f).unwrap().to_string(),
+            "lr.w.aq a0, (a1)"
+        );
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn parse_all_reports_offset() {
+        let mut program = Vec::new();
+        program.extend_from_slice(&0x00a00513u32.to_le_bytes()); // addi a0, zero, 10
+        program.extend_from_slice(&[0x05, 0x05]); // c.addi a0, 1
+        program.extend_from_slice(&0x0ab50533u32.to_le_bytes()); // add with funct7 0b101
+        let error = parse_all(&program).unwrap_err();
+        assert_eq!(
+            error,
+            ParseErrorAt {
+                offset: 6,
+                error: ParseError::InvalidFunct7 {
+                    word: 0x0ab50533,
+                    funct7: 0b101,
+                },
+            }
+        );
+        assert_eq!(error.error.trap_value(), Some(0x0ab50533));
+        assert_eq!(parse_all(&program[..6]).unwrap().len(), 2);
+        assert_eq!(
+            ParseError::invalid_register("Invalid register").with_word(0x1234),
+            ParseError::InvalidRegister { word: 0x1234 }
+        );
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn parse_all_mixed_widths() {
+        assert_eq!(instruction_length(0x0505), 2);
+        assert_eq!(instruction_length(0x0513), 4);
+        let program = [
+            0x05, 0x05, // c.addi a0, 1
+            0x33, 0x05, 0xb5, 0x00, // add a0, a0, a1
+            0x82, 0x80, // c.jr ra
+            0x13, 0x05, 0xa0, 0x00, // addi a0, zero, 10
+            0x33, 0x05, 0xb5, 0x00, // add a0, a0, a1
+        ];
+        let instructions = parse_all(&program).unwrap();
+        let widths: Vec<bool> = instructions.iter().map(|i| i.is_16bit()).collect();
+        assert_eq!(widths, [true, false, true, false, false]);
+        assert_eq!(instructions[1].raw, 0x00b50533);
+        assert_eq!(instructions[3].raw, 0x00a00513);
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn truncated_input() {
+        // the first half of addi a0, zero, 10
+        assert_eq!(parse(&[0x13, 0x05]).unwrap_err(), ParseError::Truncated);
+        assert_eq!(parse(&[0x13]).unwrap_err(), ParseError::Truncated);
+        assert_eq!(parse(&[]).unwrap_err(), ParseError::Truncated);
+        assert_eq!(
+            parse_be(&[0x00, 0x00, 0x05]).unwrap_err(),
+            ParseError::Truncated
+        );
+        let program = [
+            0x05, 0x05, // c.addi a0, 1
+            0x13, 0x05, 0xa0, 0x00, // addi a0, zero, 10
+            0x13, 0x05, // truncated addi
+        ];
+        assert_eq!(
+            parse_all(&program).unwrap_err(),
+            ParseErrorAt {
+                offset: 6,
+                error: ParseError::Truncated,
+            }
+        );
+        assert_eq!(
+            parse_all(&program[..7]).unwrap_err(),
+            ParseErrorAt {
+                offset: 6,
+                error: ParseError::Truncated,
+            }
+        );
+        assert_eq!(parse_all(&program[..6]).unwrap().len(), 2);
+        assert_eq!(parse_all(&[]).unwrap().len(), 0);
+        assert_eq!(
+            parse_lenient(&program[6..]).operation,
+            Operation::Unknown { raw: 0x0513 }
+        );
+        // no entry point panics on any prefix of the truncated addi
+        for end in 0..=4 {
+            let input = &[0x13, 0x05, 0xa0, 0x00][..end];
+            let _ = parse(input);
+            let _ = parse_be(input);
+            let _ = parse_with_pc(input, 0);
+            let _ = parse_with_isa(input, InstructionSet::I);
+            let _ = parse_all(input);
+            let _ = decode_one(input);
+            let _ = parse_lenient(input);
+        }
+    }
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn decode_one_consumes() {
+        // addi a0, zero, 10 followed by c.addi a0, 1
+        let program = [0x13, 0x05, 0xa0, 0x00, 0x05, 0x05];
+        let (addi, length) = decode_one(&program).unwrap();
+        assert_eq!(length, 4);
+        assert!(addi.is_32bit());
+        let (c_addi, length) = decode_one(&program[length..]).unwrap();
+        assert_eq!(length, 2);
+        assert!(c_addi.is_16bit());
+        assert_eq!(
+            decode_one(&program[..2]).unwrap_err(),
+            ParseError::Truncated
+        );
+    }
+
+    #[cfg(feature = "ext-f")]
+    #[test]
+    fn float_load_store() {
+        let flw = parse_32bit_operation(0x00812507).unwrap();
+        assert_eq!(
+            flw,
+            Operation::FLW {
+                imm: 8,
+                rs1: Register::SP,
+                rd: FloatRegister::F10,
+            }
+        );
+        assert_eq!(flw.to_string(), "flw fa0, 8(sp)");
+        assert!(flw.is_load());
+        let fsw = parse_32bit_operation(0xfea12e27).unwrap();
+        assert_eq!(
+            fsw,
+            Operation::FSW {
+                imm: -4,
+                rs2: FloatRegister::F10,
+                rs1: Register::SP,
+            }
+        );
+        assert_eq!(fsw.to_string(), "fsw fa0, -4(sp)");
+        assert!(fsw.is_store());
+        assert_eq!(fsw.rs1(), Some(Register::SP));
+        // fld and fsd belong to the D extension
+        assert!(parse_32bit_operation(0x00813507).is_err());
+        assert!(parse_32bit_operation(0xfea13e27).is_err());
+        let no_f = IsaConfig {
+            f: false,
+            ..IsaConfig::default()
+        };
+        assert!(parse_32bit_operation_with(0x00812507, &no_f).is_err());
+    }
+}
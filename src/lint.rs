@@ -0,0 +1,200 @@
+//! Provides lints for operations that are valid but most likely not intended.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::instructons::Operation;
+use crate::registers::Register;
+
+/// Describes a suspicious pattern found in an operation.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Lint {
+    /// The result of a computation is written to `zero` and thereby discarded, without the
+    /// operation being the canonical `nop` (`addi zero, zero, 0`).
+    DiscardedResult,
+    /// The operation writes to `zero` in an encoding the specification reserves as a HINT,
+    /// e.g. `addi zero, a0, 1` or the non-temporal locality hint `add zero, zero, a2`. These are
+    /// legal and executed as no-ops, but are also not what an assembler emits for `nop`.
+    Hint,
+}
+
+/// How much attention a [`Lint`] deserves.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    /// Legal and possibly intended, worth knowing about.
+    Hint,
+    /// Most likely a mistake.
+    Warning,
+}
+
+impl Lint {
+    /// The severity of the lint. Only [`Lint::Hint`] is not a warning.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Lint::DiscardedResult => Severity::Warning,
+            Lint::Hint => Severity::Hint,
+        }
+    }
+}
+
+/// Checks one operation for suspicious patterns. The canonical `nop` produces no lints.
+pub fn lint(op: &Operation) -> Vec<Lint> {
+    let mut lints = vec![];
+    if op.is_nop() {
+        return lints;
+    }
+    if is_hint(op) {
+        lints.push(Lint::Hint);
+    } else if discards_result(op) {
+        lints.push(Lint::DiscardedResult);
+    }
+    lints
+}
+
+/// The base integer HINT encodings: the immediate operations, `lui` and `auipc` writing to
+/// `zero`, and `add zero, zero, rs2`. The specification reserves the register-register forms
+/// with a nonzero `rs1` as HINTs too, but as those are far more likely to be a mistake they are
+/// still reported as [`Lint::DiscardedResult`].
+fn is_hint(op: &Operation) -> bool {
+    match op {
+        Operation::LUI { rd, .. }
+        | Operation::AUIPC { rd, .. }
+        | Operation::ADDI { rd, .. }
+        | Operation::SLTI { rd, .. }
+        | Operation::SLTIU { rd, .. }
+        | Operation::XORI { rd, .. }
+        | Operation::ORI { rd, .. }
+        | Operation::ANDI { rd, .. }
+        | Operation::SLLI { rd, .. }
+        | Operation::SRLI { rd, .. }
+        | Operation::SRAI { rd, .. } => *rd == Register::ZERO,
+        Operation::ADD { rs1, rd, .. } => *rd == Register::ZERO && *rs1 == Register::ZERO,
+        _ => false,
+    }
+}
+
+/// Only operations without side effects are considered, so loads, jumps, CSR accesses and
+/// atomics writing to `zero` are fine.
+fn discards_result(op: &Operation) -> bool {
+    match op {
+        Operation::ADD { rd, .. }
+        | Operation::SUB { rd, .. }
+        | Operation::SLL { rd, .. }
+        | Operation::SLT { rd, .. }
+        | Operation::SLTU { rd, .. }
+        | Operation::XOR { rd, .. }
+        | Operation::SRL { rd, .. }
+        | Operation::SRA { rd, .. }
+        | Operation::OR { rd, .. }
+        | Operation::AND { rd, .. }
+        | Operation::MUL { rd, .. }
+        | Operation::MULH { rd, .. }
+        | Operation::MULHSU { rd, .. }
+        | Operation::MULHU { rd, .. }
+        | Operation::DIV { rd, .. }
+        | Operation::DIVU { rd, .. }
+        | Operation::REM { rd, .. }
+        | Operation::REMU { rd, .. }
+        | Operation::CLMUL { rd, .. }
+        | Operation::CLMULH { rd, .. }
+        | Operation::CLMULR { rd, .. }
+        | Operation::SH1ADD { rd, .. }
+        | Operation::SH2ADD { rd, .. }
+        | Operation::SH3ADD { rd, .. }
+        | Operation::ANDN { rd, .. }
+        | Operation::ORN { rd, .. }
+        | Operation::XNOR { rd, .. }
+        | Operation::MIN { rd, .. }
+        | Operation::MINU { rd, .. }
+        | Operation::MAX { rd, .. }
+        | Operation::MAXU { rd, .. }
+        | Operation::ROL { rd, .. }
+        | Operation::ROR { rd, .. }
+        | Operation::BCLR { rd, .. }
+        | Operation::BEXT { rd, .. }
+        | Operation::BINV { rd, .. }
+        | Operation::BSET { rd, .. }
+        | Operation::CZERO_EQZ { rd, .. }
+        | Operation::CZERO_NEZ { rd, .. }
+        | Operation::RORI { rd, .. }
+        | Operation::BCLRI { rd, .. }
+        | Operation::BEXTI { rd, .. }
+        | Operation::BINVI { rd, .. }
+        | Operation::BSETI { rd, .. }
+        | Operation::CLZ { rd, .. }
+        | Operation::CTZ { rd, .. }
+        | Operation::CPOP { rd, .. }
+        | Operation::SEXT_B { rd, .. }
+        | Operation::SEXT_H { rd, .. }
+        | Operation::REV8 { rd, .. }
+        | Operation::ORC_B { rd, .. }
+        | Operation::ZEXT_H { rd, .. }
+        | Operation::ADDIW { rd, .. }
+        | Operation::SLLIW { rd, .. }
+        | Operation::SRLIW { rd, .. }
+        | Operation::SRAIW { rd, .. }
+        | Operation::ADDW { rd, .. }
+        | Operation::SUBW { rd, .. }
+        | Operation::SLLW { rd, .. }
+        | Operation::SRLW { rd, .. }
+        | Operation::SRAW { rd, .. } => *rd == Register::ZERO,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discarded_result() {
+        // add zero, a1, a2
+        let add = Operation::ADD {
+            rs2: Register::A2,
+            rs1: Register::A1,
+            rd: Register::ZERO,
+        };
+        assert_eq!(lint(&add), vec![Lint::DiscardedResult]);
+        // nop
+        let nop = Operation::ADDI {
+            imm: 0,
+            rs1: Register::ZERO,
+            rd: Register::ZERO,
+        };
+        assert_eq!(lint(&nop), vec![]);
+        // j 8
+        let jump = Operation::JAL {
+            rd: Register::ZERO,
+            imm: 8,
+        };
+        assert_eq!(lint(&jump), vec![]);
+    }
+
+    #[test]
+    fn hints() {
+        let nop = Operation::ADDI {
+            imm: 0,
+            rs1: Register::ZERO,
+            rd: Register::ZERO,
+        };
+        assert!(lint(&nop)
+            .iter()
+            .all(|lint| lint.severity() < Severity::Warning));
+        // addi zero, a0, 1
+        let addi = Operation::ADDI {
+            imm: 1,
+            rs1: Register::A0,
+            rd: Register::ZERO,
+        };
+        assert_eq!(lint(&addi), vec![Lint::Hint]);
+        // ntl.all, i.e. add zero, zero, t0
+        let ntl = Operation::ADD {
+            rs2: Register::T0,
+            rs1: Register::ZERO,
+            rd: Register::ZERO,
+        };
+        assert_eq!(lint(&ntl), vec![Lint::Hint]);
+        assert_eq!(Lint::Hint.severity(), Severity::Hint);
+        assert_eq!(Lint::DiscardedResult.severity(), Severity::Warning);
+    }
+}
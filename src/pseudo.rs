@@ -0,0 +1,314 @@
+//! Provides recognition of the common assembler pseudo-instructions.
+
+use core::fmt;
+
+use crate::instructons::{Csr, Offset, Operation};
+use crate::registers::Register;
+
+/// Describes a pseudo-instruction, i.e. an alias assemblers use for a specific form of an
+/// operation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pseudo {
+    /// `addi zero, zero, 0`
+    Nop,
+    /// `addi rd, zero, imm`
+    Li { rd: Register, imm: i32 },
+    /// `addi rd, rs, 0`
+    Mv { rd: Register, rs: Register },
+    /// `xori rd, rs, -1`
+    Not { rd: Register, rs: Register },
+    /// `sub rd, zero, rs`
+    Neg { rd: Register, rs: Register },
+    /// `sltiu rd, rs, 1`
+    Seqz { rd: Register, rs: Register },
+    /// `sltu rd, zero, rs`
+    Snez { rd: Register, rs: Register },
+    /// `jal zero, imm`
+    J { imm: i32 },
+    /// `jalr zero, 0(rs)`
+    Jr { rs: Register },
+    /// `jalr zero, 0(ra)`
+    Ret,
+    /// `csrrs rd, csr, zero`
+    Csrr { rd: Register, csr: u16 },
+    /// `csrrw zero, csr, rs`
+    Csrw { csr: u16, rs: Register },
+    /// `csrrs zero, csr, rs`
+    Csrs { csr: u16, rs: Register },
+    /// `csrrc zero, csr, rs`
+    Csrc { csr: u16, rs: Register },
+    /// `csrrwi zero, csr, zimm`
+    Csrwi { csr: u16, zimm: u8 },
+    /// `csrrsi zero, csr, zimm`
+    Csrsi { csr: u16, zimm: u8 },
+    /// `csrrci zero, csr, zimm`
+    Csrci { csr: u16, zimm: u8 },
+}
+
+impl Operation {
+    /// Returns the pseudo-instruction the operation is an alias of, if any.
+    pub fn as_pseudo(&self) -> Option<Pseudo> {
+        let pseudo = match *self {
+            Operation::ADDI {
+                imm: 0,
+                rs1: Register::ZERO,
+                rd: Register::ZERO,
+            } => Pseudo::Nop,
+            Operation::ADDI {
+                imm,
+                rs1: Register::ZERO,
+                rd,
+            } => Pseudo::Li { rd, imm },
+            Operation::ADDI { imm: 0, rs1, rd } => Pseudo::Mv { rd, rs: rs1 },
+            Operation::XORI { imm: -1, rs1, rd } => Pseudo::Not { rd, rs: rs1 },
+            Operation::SUB {
+                rs2,
+                rs1: Register::ZERO,
+                rd,
+            } => Pseudo::Neg { rd, rs: rs2 },
+            Operation::SLTIU { imm: 1, rs1, rd } => Pseudo::Seqz { rd, rs: rs1 },
+            Operation::SLTU {
+                rs2,
+                rs1: Register::ZERO,
+                rd,
+            } => Pseudo::Snez { rd, rs: rs2 },
+            Operation::JAL {
+                rd: Register::ZERO,
+                imm,
+            } => Pseudo::J { imm },
+            Operation::JALR {
+                rd: Register::ZERO,
+                rs1: Register::RA,
+                imm: 0,
+            } => Pseudo::Ret,
+            Operation::JALR {
+                rd: Register::ZERO,
+                rs1,
+                imm: 0,
+            } => Pseudo::Jr { rs: rs1 },
+            Operation::CSRRS {
+                csr,
+                rs1: Register::ZERO,
+                rd,
+            } => Pseudo::Csrr { rd, csr },
+            Operation::CSRRW {
+                csr,
+                rs1,
+                rd: Register::ZERO,
+            } => Pseudo::Csrw { csr, rs: rs1 },
+            Operation::CSRRS {
+                csr,
+                rs1,
+                rd: Register::ZERO,
+            } => Pseudo::Csrs { csr, rs: rs1 },
+            Operation::CSRRC {
+                csr,
+                rs1,
+                rd: Register::ZERO,
+            } => Pseudo::Csrc { csr, rs: rs1 },
+            Operation::CSRRWI {
+                csr,
+                zimm,
+                rd: Register::ZERO,
+            } => Pseudo::Csrwi { csr, zimm },
+            Operation::CSRRSI {
+                csr,
+                zimm,
+                rd: Register::ZERO,
+            } => Pseudo::Csrsi { csr, zimm },
+            Operation::CSRRCI {
+                csr,
+                zimm,
+                rd: Register::ZERO,
+            } => Pseudo::Csrci { csr, zimm },
+            _ => return None,
+        };
+        Some(pseudo)
+    }
+}
+
+impl fmt::Display for Pseudo {
+    /// Formats the pseudo-instruction as assembly, e.g. `mv a0, a1` or `ret`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pseudo::Nop => write!(f, "nop"),
+            Pseudo::Li { rd, imm } => write!(f, "li {}, {}", rd, imm),
+            Pseudo::Mv { rd, rs } => write!(f, "mv {}, {}", rd, rs),
+            Pseudo::Not { rd, rs } => write!(f, "not {}, {}", rd, rs),
+            Pseudo::Neg { rd, rs } => write!(f, "neg {}, {}", rd, rs),
+            Pseudo::Seqz { rd, rs } => write!(f, "seqz {}, {}", rd, rs),
+            Pseudo::Snez { rd, rs } => write!(f, "snez {}, {}", rd, rs),
+            Pseudo::J { imm } => write!(f, "j {}", Offset(*imm)),
+            Pseudo::Jr { rs } => write!(f, "jr {}", rs),
+            Pseudo::Ret => write!(f, "ret"),
+            Pseudo::Csrr { rd, csr } => write!(f, "csrr {}, {}", rd, Csr(*csr)),
+            Pseudo::Csrw { csr, rs } => write!(f, "csrw {}, {}", Csr(*csr), rs),
+            Pseudo::Csrs { csr, rs } => write!(f, "csrs {}, {}", Csr(*csr), rs),
+            Pseudo::Csrc { csr, rs } => write!(f, "csrc {}, {}", Csr(*csr), rs),
+            Pseudo::Csrwi { csr, zimm } => write!(f, "csrwi {}, {}", Csr(*csr), zimm),
+            Pseudo::Csrsi { csr, zimm } => write!(f, "csrsi {}, {}", Csr(*csr), zimm),
+            Pseudo::Csrci { csr, zimm } => write!(f, "csrci {}, {}", Csr(*csr), zimm),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    fn pseudo(word: u32) -> Option<Pseudo> {
+        crate::parse(&word.to_le_bytes())
+            .unwrap()
+            .operation
+            .as_pseudo()
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(pseudo(0x00000013), Some(Pseudo::Nop));
+        // addi a0, zero, 10
+        assert_eq!(
+            pseudo(0x00a00513),
+            Some(Pseudo::Li {
+                rd: Register::A0,
+                imm: 10,
+            })
+        );
+        // addi a0, a1, 0
+        assert_eq!(
+            pseudo(0x00058513),
+            Some(Pseudo::Mv {
+                rd: Register::A0,
+                rs: Register::A1,
+            })
+        );
+        // xori a0, a1, -1
+        assert_eq!(
+            pseudo(0xfff5c513),
+            Some(Pseudo::Not {
+                rd: Register::A0,
+                rs: Register::A1,
+            })
+        );
+        // sub a0, zero, a1
+        assert_eq!(
+            pseudo(0x40b00533),
+            Some(Pseudo::Neg {
+                rd: Register::A0,
+                rs: Register::A1,
+            })
+        );
+        // sltiu a0, a1, 1
+        assert_eq!(
+            pseudo(0x0015b513),
+            Some(Pseudo::Seqz {
+                rd: Register::A0,
+                rs: Register::A1,
+            })
+        );
+        // sltu a0, zero, a1
+        assert_eq!(
+            pseudo(0x00b03533),
+            Some(Pseudo::Snez {
+                rd: Register::A0,
+                rs: Register::A1,
+            })
+        );
+        // jal zero, -8
+        assert_eq!(pseudo(0xff9ff06f), Some(Pseudo::J { imm: -8 }));
+        // jalr zero, 0(ra)
+        assert_eq!(pseudo(0x00008067), Some(Pseudo::Ret));
+        // jalr zero, 0(t0)
+        assert_eq!(pseudo(0x00028067), Some(Pseudo::Jr { rs: Register::T0 }));
+        // addi a0, a1, 1
+        assert_eq!(pseudo(0x00158513), None);
+    }
+
+    #[test]
+    fn display() {
+        let mv = crate::parse(&0x00058513u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert_eq!(mv.to_string(), "addi a0, a1, 0");
+        assert_eq!(format!("{:#}", mv), "mv a0, a1");
+        assert_eq!(Pseudo::J { imm: -8 }.to_string(), "j -0x8");
+        assert_eq!(Pseudo::Ret.to_string(), "ret");
+        // operations without an alias are printed as usual
+        let add = crate::parse(&0x00b50533u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert_eq!(format!("{:#}", add), "add a0, a0, a1");
+    }
+    #[test]
+    fn csr_aliases() {
+        // csrrs a0, mstatus, zero
+        assert_eq!(
+            pseudo(0x30002573),
+            Some(Pseudo::Csrr {
+                rd: Register::A0,
+                csr: 0x300,
+            })
+        );
+        // csrrw zero, mtvec, a0
+        assert_eq!(
+            pseudo(0x30551073),
+            Some(Pseudo::Csrw {
+                csr: 0x305,
+                rs: Register::A0,
+            })
+        );
+        // csrrs zero, mie, a0
+        assert_eq!(
+            pseudo(0x30452073),
+            Some(Pseudo::Csrs {
+                csr: 0x304,
+                rs: Register::A0,
+            })
+        );
+        // csrrc zero, mie, a0
+        assert_eq!(
+            pseudo(0x30453073),
+            Some(Pseudo::Csrc {
+                csr: 0x304,
+                rs: Register::A0,
+            })
+        );
+        // csrrsi zero, mstatus, 8
+        assert_eq!(
+            pseudo(0x30046073),
+            Some(Pseudo::Csrsi {
+                csr: 0x300,
+                zimm: 8,
+            })
+        );
+        // csrrci zero, mstatus, 8
+        assert_eq!(
+            pseudo(0x30047073),
+            Some(Pseudo::Csrci {
+                csr: 0x300,
+                zimm: 8,
+            })
+        );
+        // csrrwi zero, 0x7c0, 1
+        assert_eq!(
+            pseudo(0x7c00d073),
+            Some(Pseudo::Csrwi {
+                csr: 0x7c0,
+                zimm: 1,
+            })
+        );
+        // csrrw a0, mscratch, a1
+        assert_eq!(pseudo(0x34059573), None);
+
+        let csrr = crate::parse(&0x30002573u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert_eq!(format!("{:#}", csrr), "csrr a0, mstatus");
+        let csrwi = crate::parse(&0x7c00d073u32.to_le_bytes())
+            .unwrap()
+            .operation;
+        assert_eq!(format!("{:#}", csrwi), "csrwi 0x7c0, 1");
+    }
+}
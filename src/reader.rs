@@ -0,0 +1,155 @@
+//! Provides parsing of instructions from a `std::io::Read` stream. Requires the `std` feature.
+
+use core::fmt;
+use std::io::{self, Read};
+
+use crate::error::ParseError;
+use crate::instructons::{width_of, Instruction, InstructionWidth};
+use crate::parse;
+
+/// Describes why an instruction could not be read from a stream.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading from the stream failed.
+    Io(io::Error),
+    /// The instruction could not be parsed.
+    Parse(ParseError),
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        ReadError::Io(error)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(error: ParseError) -> Self {
+        ReadError::Parse(error)
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(error) => write!(f, "Read failed: {}", error),
+            ReadError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+/// Parses the instructions of a stream one at a time, reading only as many bytes as the next
+/// instruction needs.
+///
+/// Instructions that can not be parsed are yielded as errors and skipped over. Iteration stops
+/// at the end of the stream, at a trailing incomplete instruction or after a read error.
+pub fn parse_reader<R: Read>(reader: R) -> impl Iterator<Item = Result<Instruction, ReadError>> {
+    InstructionReader {
+        reader,
+        done: false,
+    }
+}
+
+struct InstructionReader<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read> InstructionReader<R> {
+    /// Reads the next instruction, or returns None at the end of the stream.
+    fn read_instruction(&mut self) -> Result<Option<Instruction>, ReadError> {
+        let mut bytes = [0; 4];
+        if !self.fill(&mut bytes[..2])? {
+            return Ok(None);
+        }
+        if let InstructionWidth::Bit32 = width_of(<u16>::from_le_bytes([bytes[0], bytes[1]])) {
+            if !self.fill(&mut bytes[2..])? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(parse(&bytes)?))
+    }
+
+    /// Fills `buf` completely, returning false if the stream ends first.
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        match self.reader.read_exact(buf) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl<R: Read> Iterator for InstructionReader<R> {
+    type Item = Result<Instruction, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_instruction() {
+            Ok(Some(instruction)) => Some(Ok(instruction)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = matches!(error, ReadError::Io(_));
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "ext-c")]
+    #[test]
+    fn cursor() {
+        use crate::instructons::Operation;
+        use crate::registers::Register;
+        use std::io::Cursor;
+        use std::vec::Vec;
+
+        let program = [
+            0x13, 0x05, 0xa0, 0x00, // addi a0, zero, 10
+            0x05, 0x05, // c.addi a0, 1
+            0xff, 0xff, 0xff, 0xff, // invalid
+            0x82, 0x80, // c.jr ra
+            0x33, 0x05, // trailing half of an add
+        ];
+        let results: Vec<_> = parse_reader(Cursor::new(program)).collect();
+        assert_eq!(results.len(), 4);
+        let li = results[0].as_ref().unwrap();
+        assert!(li.is_32bit());
+        assert_eq!(
+            li.operation,
+            Operation::ADDI {
+                imm: 10,
+                rs1: Register::ZERO,
+                rd: Register::A0,
+            }
+        );
+        assert!(results[1].as_ref().unwrap().is_16bit());
+        assert!(matches!(results[2], Err(ReadError::Parse(_))));
+        assert_eq!(results[3].as_ref().unwrap().to_assembly(), "c.jr ra");
+    }
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken"))
+        }
+    }
+
+    #[test]
+    fn read_error() {
+        let mut instructions = parse_reader(Failing);
+        assert!(matches!(instructions.next(), Some(Err(ReadError::Io(_)))));
+        assert!(instructions.next().is_none());
+    }
+}
@@ -1,5 +1,12 @@
-/// Normal register type.
-#[derive(Debug, PartialEq, Clone, Copy)]
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Normal register type. Registers are ordered by their number.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Register {
     ZERO = 0,
@@ -77,40 +84,406 @@ impl TryFrom<u8> for Register {
     }
 }
 
+impl TryFrom<u32> for Register {
+    type Error = &'static str;
+    /// Takes a register number as extracted from an instruction word, failing for 32 and above.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .map_err(|_| "Invalid register")?
+            .try_into()
+    }
+}
+
+/// Lowercase ABI names, indexed by register number.
+const ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+impl Register {
+    /// Register number, e.g. 10 for `a0`.
+    pub const fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Iterates over all registers in ascending order, from `zero` to `t6`.
+    pub fn all() -> impl Iterator<Item = Register> {
+        (0u8..32).filter_map(|index| index.try_into().ok())
+    }
+
+    /// Lowercase ABI name as used by assemblers, e.g. `a0`.
+    pub fn abi_name(&self) -> &'static str {
+        ABI_NAMES[self.index() as usize]
+    }
+
+    /// Looks up a register by its lowercase ABI name. Also accepts `fp`, the alias of `s0`.
+    pub fn from_abi_name(name: &str) -> Option<Register> {
+        if name == "fp" {
+            return Some(Register::S0);
+        }
+        let index = ABI_NAMES.iter().position(|abi_name| *abi_name == name)?;
+        (index as u8).try_into().ok()
+    }
+
+    /// Numeric name of the register, e.g. `x10` for `a0`.
+    pub fn x_name(&self) -> String {
+        format!("x{}", self.index())
+    }
+
+    /// Whether a callee has to preserve the register, i.e. `sp` and `s0`-`s11`.
+    pub fn is_callee_saved(&self) -> bool {
+        matches!(
+            self,
+            Register::SP
+                | Register::S0
+                | Register::S1
+                | Register::S2
+                | Register::S3
+                | Register::S4
+                | Register::S5
+                | Register::S6
+                | Register::S7
+                | Register::S8
+                | Register::S9
+                | Register::S10
+                | Register::S11
+        )
+    }
+
+    /// Whether the caller has to save the register across calls, i.e. `ra`, the temporaries
+    /// and the arguments.
+    pub fn is_caller_saved(&self) -> bool {
+        *self == Register::RA || self.is_temporary() || self.is_argument()
+    }
+
+    /// Whether the register is one of the argument registers `a0`-`a7`.
+    pub fn is_argument(&self) -> bool {
+        (Register::A0 as u8..=Register::A7 as u8).contains(&(*self as u8))
+    }
+
+    /// Whether the register is one of the temporaries `t0`-`t6`.
+    pub fn is_temporary(&self) -> bool {
+        matches!(
+            self,
+            Register::T0
+                | Register::T1
+                | Register::T2
+                | Register::T3
+                | Register::T4
+                | Register::T5
+                | Register::T6
+        )
+    }
+}
+
+impl fmt::Display for Register {
+    /// Formats the register with its lowercase ABI name, e.g. `a0`, or with its numeric
+    /// name, e.g. `x10`, when the alternate flag (`{:#}`) is given.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "x{}", *self as u8)
+        } else {
+            f.write_str(self.abi_name())
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = &'static str;
+
+    /// Parses an ABI name, e.g. `a0` or its alias `fp` for `s0`, or a numeric name, e.g. `x10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(register) = Register::from_abi_name(s) {
+            return Ok(register);
+        }
+        match s.strip_prefix('x') {
+            Some(number) if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) => {
+                number
+                    .parse::<u8>()
+                    .map_err(|_| "Invalid register")?
+                    .try_into()
+            }
+            _ => Err("Invalid register"),
+        }
+    }
+}
+
+/// Floating-point register type, used by the F and D extensions.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum FloatRegister {
+    F0 = 0,
+    F1 = 1,
+    F2 = 2,
+    F3 = 3,
+    F4 = 4,
+    F5 = 5,
+    F6 = 6,
+    F7 = 7,
+    F8 = 8,
+    F9 = 9,
+    F10 = 10,
+    F11 = 11,
+    F12 = 12,
+    F13 = 13,
+    F14 = 14,
+    F15 = 15,
+    F16 = 16,
+    F17 = 17,
+    F18 = 18,
+    F19 = 19,
+    F20 = 20,
+    F21 = 21,
+    F22 = 22,
+    F23 = 23,
+    F24 = 24,
+    F25 = 25,
+    F26 = 26,
+    F27 = 27,
+    F28 = 28,
+    F29 = 29,
+    F30 = 30,
+    F31 = 31,
+}
+
+impl TryFrom<u8> for FloatRegister {
+    type Error = &'static str;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FloatRegister::F0),
+            1 => Ok(FloatRegister::F1),
+            2 => Ok(FloatRegister::F2),
+            3 => Ok(FloatRegister::F3),
+            4 => Ok(FloatRegister::F4),
+            5 => Ok(FloatRegister::F5),
+            6 => Ok(FloatRegister::F6),
+            7 => Ok(FloatRegister::F7),
+            8 => Ok(FloatRegister::F8),
+            9 => Ok(FloatRegister::F9),
+            10 => Ok(FloatRegister::F10),
+            11 => Ok(FloatRegister::F11),
+            12 => Ok(FloatRegister::F12),
+            13 => Ok(FloatRegister::F13),
+            14 => Ok(FloatRegister::F14),
+            15 => Ok(FloatRegister::F15),
+            16 => Ok(FloatRegister::F16),
+            17 => Ok(FloatRegister::F17),
+            18 => Ok(FloatRegister::F18),
+            19 => Ok(FloatRegister::F19),
+            20 => Ok(FloatRegister::F20),
+            21 => Ok(FloatRegister::F21),
+            22 => Ok(FloatRegister::F22),
+            23 => Ok(FloatRegister::F23),
+            24 => Ok(FloatRegister::F24),
+            25 => Ok(FloatRegister::F25),
+            26 => Ok(FloatRegister::F26),
+            27 => Ok(FloatRegister::F27),
+            28 => Ok(FloatRegister::F28),
+            29 => Ok(FloatRegister::F29),
+            30 => Ok(FloatRegister::F30),
+            31 => Ok(FloatRegister::F31),
+            _ => Err("Invalid register"),
+        }
+    }
+}
+
+impl TryFrom<u32> for FloatRegister {
+    type Error = &'static str;
+    /// Takes a register number as extracted from an instruction word, failing for 32 and above.
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .map_err(|_| "Invalid register")?
+            .try_into()
+    }
+}
+
+/// Lowercase ABI names of the floating-point registers, indexed by register number.
+const FLOAT_ABI_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+impl FloatRegister {
+    /// Lowercase ABI name as used by assemblers, e.g. `fa0`.
+    pub fn abi_name(&self) -> &'static str {
+        FLOAT_ABI_NAMES[*self as usize]
+    }
+
+    /// Looks up a floating-point register by its lowercase ABI name.
+    pub fn from_abi_name(name: &str) -> Option<FloatRegister> {
+        let index = FLOAT_ABI_NAMES
+            .iter()
+            .position(|abi_name| *abi_name == name)?;
+        (index as u8).try_into().ok()
+    }
+}
+
+impl fmt::Display for FloatRegister {
+    /// Formats the register with its lowercase ABI name, e.g. `fa0`, or with its numeric
+    /// name, e.g. `f10`, when the alternate flag (`{:#}`) is given.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "f{}", *self as u8)
+        } else {
+            f.write_str(self.abi_name())
+        }
+    }
+}
+
 /// Creates a register list from a bit array.
 pub fn register_list_from_bit_array(bit_array: u32) -> Vec<Register> {
-    let mut ret = vec![];
-    for i in 0u8..32u8 {
-        if (bit_array >> i) & 0b1 == 0b1 {
-            ret.push(i.try_into().unwrap())
+    Register::all()
+        .filter(|register| (bit_array >> register.index()) & 0b1 == 0b1)
+        .collect()
+}
+
+/// Register list, printed as e.g. `{ra, s0, a0}`. With the alternate flag (`{:#}`) runs of
+/// registers with consecutive ABI names are collapsed into ranges, e.g. `{s0-s2, ra}`.
+#[derive(Debug, Clone, Copy)]
+pub struct RegisterList<'a>(pub &'a [Register]);
+
+/// Splits an ABI name into its prefix and index, e.g. `s10` into `("s", 10)`.
+fn split_abi_name(register: &Register) -> Option<(&'static str, u8)> {
+    let name = register.abi_name();
+    let split = name.find(|c: char| c.is_ascii_digit())?;
+    Some((&name[..split], name[split..].parse().ok()?))
+}
+
+/// Whether `next` directly follows `register` by ABI name, e.g. `s2` after `s1`.
+fn follows(register: &Register, next: &Register) -> bool {
+    match (split_abi_name(register), split_abi_name(next)) {
+        (Some((prefix, index)), Some((next_prefix, next_index))) => {
+            prefix == next_prefix && index + 1 == next_index
         }
+        _ => false,
     }
-    ret
+}
+
+impl fmt::Display for RegisterList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        let mut i = 0;
+        while i < self.0.len() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            let mut end = i;
+            if f.alternate() {
+                while end + 1 < self.0.len() && follows(&self.0[end], &self.0[end + 1]) {
+                    end += 1;
+                }
+            }
+            if end > i {
+                write!(f, "{}-{}", self.0[i], self.0[end])?;
+            } else {
+                write!(f, "{}", self.0[i])?;
+            }
+            i = end + 1;
+        }
+        f.write_str("}")
+    }
+}
+
+/// Creates a bit array from a register list, the inverse of [`register_list_from_bit_array`].
+pub fn register_list_to_bit_array(registers: &[Register]) -> u32 {
+    registers
+        .iter()
+        .fold(0, |bit_array, register| bit_array | 1 << *register as u8)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
 
     #[test]
     fn from_u8_to_register() {
-        assert_eq!(0.try_into(), Ok(Register::ZERO));
-        assert_eq!(1.try_into(), Ok(Register::RA));
-        assert_eq!(2.try_into(), Ok(Register::SP));
-        assert_eq!(3.try_into(), Ok(Register::GP));
-        assert_eq!(4.try_into(), Ok(Register::TP));
-        assert_eq!(5.try_into(), Ok(Register::T0));
-        assert_eq!(6.try_into(), Ok(Register::T1));
-        assert_eq!(7.try_into(), Ok(Register::T2));
-        assert_eq!(8.try_into(), Ok(Register::S0));
-        assert_eq!(9.try_into(), Ok(Register::S1));
-        assert_eq!(10.try_into(), Ok(Register::A0));
-        assert_eq!(11.try_into(), Ok(Register::A1));
-        assert_eq!(12.try_into(), Ok(Register::A2));
-        assert_eq!(13.try_into(), Ok(Register::A3));
-        assert_eq!(14.try_into(), Ok(Register::A4));
-        assert_eq!(15.try_into(), Ok(Register::A5));
-        assert_eq!(16.try_into(), Ok(Register::A6));
+        assert_eq!(0u8.try_into(), Ok(Register::ZERO));
+        assert_eq!(1u8.try_into(), Ok(Register::RA));
+        assert_eq!(2u8.try_into(), Ok(Register::SP));
+        assert_eq!(3u8.try_into(), Ok(Register::GP));
+        assert_eq!(4u8.try_into(), Ok(Register::TP));
+        assert_eq!(5u8.try_into(), Ok(Register::T0));
+        assert_eq!(6u8.try_into(), Ok(Register::T1));
+        assert_eq!(7u8.try_into(), Ok(Register::T2));
+        assert_eq!(8u8.try_into(), Ok(Register::S0));
+        assert_eq!(9u8.try_into(), Ok(Register::S1));
+        assert_eq!(10u8.try_into(), Ok(Register::A0));
+        assert_eq!(11u8.try_into(), Ok(Register::A1));
+        assert_eq!(12u8.try_into(), Ok(Register::A2));
+        assert_eq!(13u8.try_into(), Ok(Register::A3));
+        assert_eq!(14u8.try_into(), Ok(Register::A4));
+        assert_eq!(15u8.try_into(), Ok(Register::A5));
+        assert_eq!(16u8.try_into(), Ok(Register::A6));
+    }
+
+    #[test]
+    fn from_u32_to_register() {
+        assert_eq!(Register::try_from(0u32), Ok(Register::ZERO));
+        assert_eq!(Register::try_from(31u32), Ok(Register::T6));
+        assert!(Register::try_from(32u32).is_err());
+        assert!(Register::try_from(0x100u32).is_err());
+        assert_eq!(FloatRegister::try_from(31u32), Ok(FloatRegister::F31));
+        assert!(FloatRegister::try_from(32u32).is_err());
+    }
+
+    #[test]
+    fn index() {
+        assert_eq!(Register::ZERO.index(), 0);
+        assert_eq!(Register::SP.index(), 2);
+        assert_eq!(Register::A0.index(), 10);
+        assert_eq!(Register::S2.index(), 18);
+        assert_eq!(Register::T6.index(), 31);
+        const RA: u8 = Register::RA.index();
+        assert_eq!(RA, 1);
+    }
+
+    #[test]
+    fn all_registers() {
+        let registers: Vec<Register> = Register::all().collect();
+        assert_eq!(registers.len(), 32);
+        assert_eq!(registers[0], Register::ZERO);
+        assert_eq!(registers[31], Register::T6);
+        for (i, register) in registers.iter().enumerate() {
+            assert_eq!(register.index() as usize, i);
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(Register::ZERO < Register::RA && Register::RA < Register::SP);
+        assert!(Register::A7 < Register::S2);
+        let mut registers = vec![Register::T6, Register::S2, Register::A0, Register::RA];
+        registers.sort();
+        assert_eq!(
+            registers,
+            [Register::RA, Register::A0, Register::S2, Register::T6]
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Register::ZERO.to_string(), "zero");
+        assert_eq!(Register::RA.to_string(), "ra");
+        assert_eq!(Register::SP.to_string(), "sp");
+        assert_eq!(Register::S0.to_string(), "s0");
+        assert_eq!(Register::A0.to_string(), "a0");
+        assert_eq!(Register::S11.to_string(), "s11");
+        assert_eq!(Register::T6.to_string(), "t6");
+        assert_eq!(format!("{:?}", Register::A0), "A0");
+    }
+
+    #[test]
+    fn numeric_name() {
+        assert_eq!(Register::ZERO.x_name(), "x0");
+        assert_eq!(Register::A0.x_name(), "x10");
+        assert_eq!(Register::T6.x_name(), "x31");
+        assert_eq!(format!("{:#}", Register::A0), "x10");
+        assert_eq!(format!("{}", Register::A0), "a0");
     }
 
     #[test]
@@ -151,4 +524,127 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn full_register_list() {
+        let registers = register_list_from_bit_array(u32::MAX);
+        assert_eq!(registers.len(), 32);
+        for (i, register) in registers.into_iter().enumerate() {
+            assert_eq!(register as usize, i);
+        }
+    }
+
+    #[test]
+    fn register_list_display() {
+        assert_eq!(RegisterList(&[]).to_string(), "{}");
+        assert_eq!(format!("{:#}", RegisterList(&[])), "{}");
+        assert_eq!(format!("{:#}", RegisterList(&[Register::RA])), "{ra}");
+        let registers = [Register::RA, Register::S0, Register::A0];
+        assert_eq!(RegisterList(&registers).to_string(), "{ra, s0, a0}");
+        assert_eq!(format!("{:#}", RegisterList(&registers)), "{ra, s0, a0}");
+        let registers = [Register::S0, Register::S1, Register::S2, Register::RA];
+        assert_eq!(RegisterList(&registers).to_string(), "{s0, s1, s2, ra}");
+        assert_eq!(format!("{:#}", RegisterList(&registers)), "{s0-s2, ra}");
+        let registers = register_list_from_bit_array(0b1111_1100_0000_0000);
+        assert_eq!(format!("{:#}", RegisterList(&registers)), "{a0-a5}");
+        let registers = [Register::T2, Register::T3, Register::A1, Register::A2];
+        assert_eq!(format!("{:#}", RegisterList(&registers)), "{t2-t3, a1-a2}");
+    }
+
+    #[test]
+    fn register_list_round_trip() {
+        assert_eq!(register_list_to_bit_array(&[]), 0);
+        assert_eq!(
+            register_list_to_bit_array(&[Register::A0, Register::ZERO]),
+            0b10000000001
+        );
+        for bit_array in [0, 0b1, 0b111, 0xffff, 0x8000_0000, 0xdead_beef, u32::MAX] {
+            assert_eq!(
+                register_list_to_bit_array(&register_list_from_bit_array(bit_array)),
+                bit_array
+            );
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("zero".parse(), Ok(Register::ZERO));
+        assert_eq!("a0".parse(), Ok(Register::A0));
+        assert_eq!("s11".parse(), Ok(Register::S11));
+        assert_eq!("t6".parse(), Ok(Register::T6));
+        assert_eq!("fp".parse(), Ok(Register::S0));
+        assert_eq!("x0".parse(), Ok(Register::ZERO));
+        assert_eq!("x10".parse(), Ok(Register::A0));
+        assert_eq!("x31".parse(), Ok(Register::T6));
+        assert!("x32".parse::<Register>().is_err());
+        assert!("x".parse::<Register>().is_err());
+        assert!("x+1".parse::<Register>().is_err());
+        assert!("A0".parse::<Register>().is_err());
+        assert!("a8".parse::<Register>().is_err());
+    }
+
+    #[test]
+    fn abi_name_round_trip() {
+        for i in 0u8..32 {
+            let register: Register = i.try_into().unwrap();
+            assert_eq!(Register::from_abi_name(register.abi_name()), Some(register));
+        }
+        assert_eq!(Register::from_abi_name("fp"), Some(Register::S0));
+        assert_eq!(Register::from_abi_name("x10"), None);
+    }
+
+    #[test]
+    fn calling_convention() {
+        for register in [Register::SP, Register::S0, Register::S1, Register::S11] {
+            assert!(register.is_callee_saved());
+            assert!(!register.is_caller_saved());
+        }
+        for register in [Register::RA, Register::T0, Register::T6, Register::A0] {
+            assert!(register.is_caller_saved());
+            assert!(!register.is_callee_saved());
+        }
+        for register in [Register::ZERO, Register::GP, Register::TP] {
+            assert!(!register.is_callee_saved());
+            assert!(!register.is_caller_saved());
+        }
+        assert!(Register::A0.is_argument());
+        assert!(Register::A7.is_argument());
+        assert!(!Register::S2.is_argument());
+        assert!(Register::T2.is_temporary());
+        assert!(Register::T3.is_temporary());
+        assert!(!Register::RA.is_temporary());
+        assert!(!Register::A0.is_temporary());
+    }
+
+    #[test]
+    fn from_u8_to_float_register() {
+        assert_eq!(0u8.try_into(), Ok(FloatRegister::F0));
+        assert_eq!(10u8.try_into(), Ok(FloatRegister::F10));
+        assert_eq!(31u8.try_into(), Ok(FloatRegister::F31));
+        assert!(FloatRegister::try_from(32u8).is_err());
+    }
+
+    #[test]
+    fn float_display() {
+        assert_eq!(FloatRegister::F0.to_string(), "ft0");
+        assert_eq!(FloatRegister::F8.to_string(), "fs0");
+        assert_eq!(FloatRegister::F10.to_string(), "fa0");
+        assert_eq!(FloatRegister::F18.to_string(), "fs2");
+        assert_eq!(FloatRegister::F28.to_string(), "ft8");
+        assert_eq!(FloatRegister::F31.to_string(), "ft11");
+        assert_eq!(format!("{:#}", FloatRegister::F10), "f10");
+    }
+
+    #[test]
+    fn float_abi_name_round_trip() {
+        for i in 0u8..32 {
+            let register: FloatRegister = i.try_into().unwrap();
+            assert_eq!(register as u8, i);
+            assert_eq!(
+                FloatRegister::from_abi_name(register.abi_name()),
+                Some(register)
+            );
+        }
+        assert_eq!(FloatRegister::from_abi_name("a0"), None);
+    }
 }
@@ -0,0 +1,86 @@
+//! Provides the rounding modes of floating-point operations.
+
+use core::fmt;
+
+/// Rounding mode, encoded in the funct3 field (bits `[14:12]`) of floating-point arithmetic.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even.
+    Rne = 0b000,
+    /// Round towards zero.
+    Rtz = 0b001,
+    /// Round down, towards negative infinity.
+    Rdn = 0b010,
+    /// Round up, towards positive infinity.
+    Rup = 0b011,
+    /// Round to nearest, ties to max magnitude.
+    Rmm = 0b100,
+    /// Use the rounding mode in the `frm` CSR.
+    Dyn = 0b111,
+}
+
+impl TryFrom<u8> for RoundingMode {
+    type Error = &'static str;
+    /// Fails for the reserved encodings `101` and `110`.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(RoundingMode::Rne),
+            0b001 => Ok(RoundingMode::Rtz),
+            0b010 => Ok(RoundingMode::Rdn),
+            0b011 => Ok(RoundingMode::Rup),
+            0b100 => Ok(RoundingMode::Rmm),
+            0b111 => Ok(RoundingMode::Dyn),
+            _ => Err("Invalid rounding mode"),
+        }
+    }
+}
+
+impl RoundingMode {
+    /// Lowercase name as used by assemblers, e.g. `rtz`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoundingMode::Rne => "rne",
+            RoundingMode::Rtz => "rtz",
+            RoundingMode::Rdn => "rdn",
+            RoundingMode::Rup => "rup",
+            RoundingMode::Rmm => "rmm",
+            RoundingMode::Dyn => "dyn",
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn from_funct3() {
+        assert_eq!(0b000.try_into(), Ok(RoundingMode::Rne));
+        assert_eq!(0b001.try_into(), Ok(RoundingMode::Rtz));
+        assert_eq!(0b010.try_into(), Ok(RoundingMode::Rdn));
+        assert_eq!(0b011.try_into(), Ok(RoundingMode::Rup));
+        assert_eq!(0b100.try_into(), Ok(RoundingMode::Rmm));
+        assert_eq!(0b111.try_into(), Ok(RoundingMode::Dyn));
+        assert!(RoundingMode::try_from(0b101).is_err());
+        assert!(RoundingMode::try_from(0b110).is_err());
+        for funct3 in [0b000, 0b001, 0b010, 0b011, 0b100, 0b111] {
+            assert_eq!(RoundingMode::try_from(funct3).unwrap() as u8, funct3);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(RoundingMode::Rne.to_string(), "rne");
+        assert_eq!(RoundingMode::Rmm.to_string(), "rmm");
+        assert_eq!(RoundingMode::Dyn.to_string(), "dyn");
+    }
+}
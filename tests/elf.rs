@@ -0,0 +1,34 @@
+#![cfg(feature = "elf")]
+
+use riscv_instruction_parser::elf::{disassemble_text, ElfError};
+use riscv_instruction_parser::instructons::Operation;
+use riscv_instruction_parser::registers::Register;
+
+/// RV32IC executable with a `.text` section at 0x10000 holding
+/// `addi a0, zero, 10; c.addi a0, 1; add a0, a0, a1; c.jr ra`.
+const TINY: &[u8] = include_bytes!("data/tiny.elf");
+
+#[test]
+fn text_section() {
+    let instructions = disassemble_text(TINY).unwrap();
+    let addresses: Vec<u32> = instructions.iter().map(|(pc, _)| *pc).collect();
+    assert_eq!(addresses, [0x10000, 0x10004, 0x10006, 0x1000a]);
+    assert_eq!(
+        instructions[2].1.operation,
+        Operation::ADD {
+            rs2: Register::A1,
+            rs1: Register::A0,
+            rd: Register::A0,
+        }
+    );
+    assert!(instructions[3].1.is_16bit());
+    assert_eq!(instructions[3].1.to_string(), "jalr zero, 0(ra)");
+}
+
+#[test]
+fn not_an_elf_file() {
+    assert!(matches!(
+        disassemble_text(&[0x13, 0x05, 0xa0, 0x00]),
+        Err(ElfError::Object(_))
+    ));
+}
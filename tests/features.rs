@@ -0,0 +1,43 @@
+//! Checks that the encodings of disabled extension features fail to parse. Run with
+//! `cargo test --no-default-features --features std`, which also runs the unit tests that do
+//! not need any extension.
+
+use riscv_instruction_parser::parse;
+
+#[test]
+fn base_is_always_decoded() {
+    // add a0, a0, a1
+    assert!(parse(&0x00b50533u32.to_le_bytes()).is_ok());
+}
+
+#[cfg(not(feature = "ext-m"))]
+#[test]
+fn m_disabled() {
+    // mul a0, a1, a2
+    assert!(parse(&0x02c58533u32.to_le_bytes()).is_err());
+}
+
+#[cfg(not(feature = "ext-a"))]
+#[test]
+fn a_disabled() {
+    // amoadd.w a0, a2, (a1)
+    assert!(parse(&0x00c5a52fu32.to_le_bytes()).is_err());
+}
+
+#[cfg(not(feature = "ext-f"))]
+#[test]
+fn f_disabled() {
+    // fadd.s fa0, fa1, fa2
+    assert!(parse(&0x00c5f553u32.to_le_bytes()).is_err());
+}
+
+#[cfg(not(feature = "ext-c"))]
+#[test]
+fn c_disabled() {
+    use riscv_instruction_parser::error::ParseError;
+
+    // c.addi a0, 1
+    assert!(parse(&[0x05, 0x05]).is_err());
+    // the all-zero halfword is still illegal rather than a disabled extension
+    assert_eq!(parse(&[0x00, 0x00]).unwrap_err(), ParseError::Illegal);
+}